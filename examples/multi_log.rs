@@ -2,12 +2,22 @@ use eframe::NativeOptions;
 
 fn main() {
     // Create an EguiLogger; multi_log will take care of initialization.
-    let egui_logger = Box::new(egui_logger::builder().build());
+    let egui_logger = Box::new(
+        egui_logger::builder()
+            .max_level(log::LevelFilter::Debug)
+            .build(),
+    );
+
+    // multi_log sets the global max level, so records above it never reach the loggers.
+    let max_level = egui_logger
+        .max_level()
+        .to_level()
+        .expect("The max level is not Off");
 
     // And add another one.
     let env_logger = Box::new(env_logger::builder().default_format().build());
 
-    multi_log::MultiLogger::init(vec![egui_logger, env_logger], log::Level::Debug)
+    multi_log::MultiLogger::init(vec![egui_logger, env_logger], max_level)
         .expect("Error initializing multi_logger");
 
     eframe::run_native(
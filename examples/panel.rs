@@ -0,0 +1,45 @@
+use eframe::NativeOptions;
+
+fn main() {
+    egui_logger::builder()
+        .init()
+        .expect("Error initializing logger");
+
+    eframe::run_native(
+        "egui_logger",
+        NativeOptions::default(),
+        Box::new(|_cc| Ok(Box::new(PanelApp))),
+    )
+    .expect("Couldn't run eframe app");
+}
+
+#[derive(Default)]
+struct PanelApp;
+
+impl eframe::App for PanelApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // the logger is docked at the bottom instead of being shown in a window
+        egui::TopBottomPanel::bottom("log")
+            .resizable(true)
+            .show(ctx, |ui| {
+                egui_logger::logger_ui()
+                    .fill_height(true) // fill the whole panel
+                    .show(ui)
+            });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if ui.button("This produces Debug Info").clicked() {
+                log::debug!("Very verbose Debug Info")
+            }
+            if ui.button("This produces an Info").clicked() {
+                log::info!("Some Info");
+            }
+            if ui.button("This produces an Error").clicked() {
+                log::error!("Error doing Something");
+            }
+            if ui.button("This produces a Warning").clicked() {
+                log::warn!("Warn about something")
+            }
+        });
+    }
+}
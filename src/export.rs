@@ -0,0 +1,125 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::format::{format_text, FormatOptions, Padding, TimeFormat};
+use crate::{lock_logger, LogEntry, Record};
+
+/// Formats a record as a line of text, the same way the records are exported.
+/// With the options of `LoggerUi::format_options()` it matches the text copied from the log.
+///
+/// ```rust
+/// # let entries = egui_logger::snapshot();
+/// for entry in &entries {
+///     println!("{}", egui_logger::format_entry(entry, egui_logger::FormatOptions::new()));
+/// }
+/// ```
+pub fn format_entry(entry: &LogEntry, options: FormatOptions) -> String {
+    let padding = Padding {
+        target: options.target_width,
+        ..Padding::default()
+    };
+    // only "Since Start" needs the start time, so the buffer isn't locked otherwise
+    let start_time = if options.time && options.time_format == TimeFormat::SinceStart {
+        crate::start_time()
+    } else {
+        entry.time
+    };
+    format_text(&options, entry.into(), start_time, padding)
+}
+
+/// Formats all records in the buffer as text, one line per record.
+fn log_text() -> String {
+    let logger = lock_logger();
+    let options = FormatOptions::new();
+    let mut text = String::new();
+    for record in &logger.logs {
+        text.push_str(&format_text(
+            &options,
+            record.into(),
+            logger.start_time,
+            Padding::default(),
+        ));
+        text.push('\n');
+    }
+    text
+}
+
+/// Formats a record as a `logfmt` line, without the trailing newline.
+pub(crate) fn logfmt_line(record: &Record) -> String {
+    let mut line = format!(
+        "time={} level={} target={} msg={}",
+        record
+            .time
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        record.level.as_str().to_lowercase(),
+        logfmt_value(&record.target),
+        logfmt_value(&record.message)
+    );
+    if let Some(location) = &record.location {
+        let _ = write!(line, " location={}", logfmt_value(location));
+    }
+    line
+}
+
+/// Quotes the value if it contains spaces or characters that would break the key=value pairs.
+fn logfmt_value(value: &str) -> String {
+    if !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '=' | '\\'))
+    {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes all records in the buffer to a text file, one line per record.
+///
+/// Useful to attach the log to a bug report, see also `export_gz()` with the `flate2` feature.
+pub fn write_to_file(path: impl AsRef<Path>) -> std::io::Result<()> {
+    // format first, so the buffer isn't locked while writing
+    let text = log_text();
+    std::fs::write(path, text)
+}
+
+/// Same as [`write_to_file()`], but gzip compressed.
+#[cfg(feature = "flate2")]
+pub fn export_gz(path: impl AsRef<Path>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let text = log_text();
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    encoder.write_all(text.as_bytes())?;
+    encoder.finish()?.flush()
+}
+
+/// Writes all records in the buffer to a file in the `logfmt` format
+/// (`time=... level=... target=... msg=...`), one line per record.
+///
+/// Useful to feed the log into tools that ingest `logfmt`.
+pub fn export_logfmt(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let text = {
+        let logger = lock_logger();
+        let mut text = String::new();
+        for record in &logger.logs {
+            text.push_str(&logfmt_line(record));
+            text.push('\n');
+        }
+        text
+    };
+    std::fs::write(path, text)
+}
@@ -0,0 +1,428 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::{LogEntry, Record};
+
+/// The precision of the timestamps.
+/// See [`FormatOptions::time_precision()`] and `LoggerUi::time_precision()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimePrecision {
+    /// e.g. `12:30:05`
+    #[default]
+    Seconds,
+    /// e.g. `12:30:05.123`
+    Milliseconds,
+}
+
+/// How the timestamps are formatted.
+/// See [`FormatOptions::time_format()`] and `LoggerUi::time_format()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimeFormat {
+    /// The date and time in UTC, as RFC 3339.
+    Utc,
+    /// The local time of day.
+    #[default]
+    LocalTime,
+    /// The local date and time, e.g. `2024-05-01 12:30:05`.
+    LocalDateTime,
+    /// The time since the logger was started, see [`crate::reset_start_time()`].
+    SinceStart,
+    /// The time since the record was logged, e.g. `3s ago`.
+    Ago,
+}
+
+/// Where the timestamps are shown.
+/// See [`FormatOptions::time_position()`] and `LoggerUi::time_position()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimePosition {
+    /// Before the level, e.g. `12:30:05 [INFO ] app: started`.
+    #[default]
+    Leading,
+    /// After the message, e.g. `[INFO ] app: started  12:30:05`.
+    Trailing,
+}
+
+pub(crate) type LevelLabel = Arc<dyn Fn(log::Level) -> String + Send + Sync>;
+
+/// The alignment of the level within its column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum LevelAlign {
+    #[default]
+    Left,
+    // only the ui can change the alignment
+    #[cfg(feature = "ui")]
+    Center,
+    #[cfg(feature = "ui")]
+    Right,
+}
+
+/// The widths of the sequence number, time and target columns.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Padding {
+    pub(crate) seq: usize,
+    pub(crate) time: usize,
+    pub(crate) target: usize,
+}
+
+/// The parts of a record that are formatted, borrowed from a [`Record`] or a [`LogEntry`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RecordParts<'a> {
+    pub(crate) seq: u64,
+    pub(crate) time: chrono::DateTime<chrono::Local>,
+    pub(crate) level: log::Level,
+    pub(crate) target: &'a str,
+    pub(crate) message: &'a str,
+}
+
+impl<'a> From<&'a Record> for RecordParts<'a> {
+    fn from(record: &'a Record) -> Self {
+        Self {
+            seq: record.seq,
+            time: record.time,
+            level: record.level,
+            target: &record.target,
+            message: &record.message,
+        }
+    }
+}
+
+impl<'a> From<&'a LogEntry> for RecordParts<'a> {
+    fn from(entry: &'a LogEntry) -> Self {
+        Self {
+            seq: entry.seq,
+            time: entry.time,
+            level: entry.level,
+            target: &entry.target,
+            message: &entry.message,
+        }
+    }
+}
+
+/// How a record is formatted by [`format_entry()`](crate::format_entry()).
+///
+/// `LoggerUi::format_options()` returns the options the log viewer formats its records with.
+///
+/// ```rust
+/// use egui_logger::FormatOptions;
+///
+/// let options = FormatOptions::new().time(false).target_width(24);
+/// ```
+#[derive(Clone)]
+pub struct FormatOptions {
+    pub(crate) time: bool,
+    pub(crate) time_format: TimeFormat,
+    pub(crate) time_precision: TimePrecision,
+    pub(crate) time_position: TimePosition,
+    pub(crate) include_seq: bool,
+    pub(crate) level: bool,
+    pub(crate) level_label: Option<LevelLabel>,
+    pub(crate) level_brackets: bool,
+    pub(crate) level_align: LevelAlign,
+    pub(crate) target: bool,
+    pub(crate) target_separator: String,
+    pub(crate) target_width: usize,
+    pub(crate) escape_control_chars: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            time: true,
+            time_format: TimeFormat::LocalDateTime,
+            time_precision: TimePrecision::Milliseconds,
+            time_position: TimePosition::Leading,
+            include_seq: false,
+            level: true,
+            level_label: None,
+            level_brackets: true,
+            level_align: LevelAlign::Left,
+            target: true,
+            target_separator: ": ".to_string(),
+            target_width: 0,
+            escape_control_chars: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for FormatOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FormatOptions")
+            .field("time", &self.time)
+            .field("time_format", &self.time_format)
+            .field("time_precision", &self.time_precision)
+            .field("time_position", &self.time_position)
+            .field("include_seq", &self.include_seq)
+            .field("level", &self.level)
+            .field("level_brackets", &self.level_brackets)
+            .field("level_align", &self.level_align)
+            .field("target", &self.target)
+            .field("target_separator", &self.target_separator)
+            .field("target_width", &self.target_width)
+            .field("escape_control_chars", &self.escape_control_chars)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FormatOptions {
+    /// Creates options that include the time, level and target,
+    /// like the lines of [`write_to_file()`](crate::write_to_file()).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Includes the time, e.g. `2024-05-01 12:30:05.123`.
+    pub fn time(mut self, enable: bool) -> Self {
+        self.time = enable;
+        self
+    }
+
+    /// Sets the format of the time.
+    /// Defaults to [`TimeFormat::LocalDateTime`].
+    pub fn time_format(mut self, format: TimeFormat) -> Self {
+        self.time_format = format;
+        self
+    }
+
+    /// Sets the precision of the time.
+    /// Defaults to [`TimePrecision::Milliseconds`].
+    pub fn time_precision(mut self, precision: TimePrecision) -> Self {
+        self.time_precision = precision;
+        self
+    }
+
+    /// Sets whether the time is put before the level or after the message.
+    /// Defaults to [`TimePosition::Leading`].
+    pub fn time_position(mut self, position: TimePosition) -> Self {
+        self.time_position = position;
+        self
+    }
+
+    /// Includes the level, padded to the longest level, e.g. `[WARN ]`.
+    pub fn level(mut self, enable: bool) -> Self {
+        self.level = enable;
+        self
+    }
+
+    /// Sets a custom label for the levels, the level is padded to the longest label.
+    /// Defaults to the level name.
+    pub fn level_label(
+        mut self,
+        label: impl Fn(log::Level) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.level_label = Some(Arc::new(label));
+        self
+    }
+
+    /// Puts brackets around the level, e.g. `[WARN ]`.
+    /// Defaults to true.
+    pub fn level_brackets(mut self, enable: bool) -> Self {
+        self.level_brackets = enable;
+        self
+    }
+
+    /// Includes the target, e.g. `my_app::net: `.
+    pub fn target(mut self, enable: bool) -> Self {
+        self.target = enable;
+        self
+    }
+
+    /// Sets the text between the target and the message.
+    /// Defaults to `": "`.
+    pub fn target_separator(mut self, separator: impl Into<String>) -> Self {
+        self.target_separator = separator.into();
+        self
+    }
+
+    /// Pads the target to `width` characters, so the messages of several lines line up.
+    pub fn target_width(mut self, width: usize) -> Self {
+        self.target_width = width;
+        self
+    }
+}
+
+/// Formats the time of a record, [`TimeFormat::SinceStart`] is relative to `start_time`.
+pub(crate) fn format_time(
+    time: chrono::DateTime<chrono::Local>,
+    options: &FormatOptions,
+    start_time: chrono::DateTime<chrono::Local>,
+) -> String {
+    match (options.time_format, options.time_precision) {
+        (TimeFormat::Utc, TimePrecision::Seconds) => time
+            .to_utc()
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        (TimeFormat::Utc, TimePrecision::Milliseconds) => time
+            .to_utc()
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        (TimeFormat::LocalTime, TimePrecision::Seconds) => time.format("%T").to_string(),
+        (TimeFormat::LocalTime, TimePrecision::Milliseconds) => time.format("%T%.3f").to_string(),
+        (TimeFormat::LocalDateTime, TimePrecision::Seconds) => {
+            time.format("%Y-%m-%d %T").to_string()
+        }
+        (TimeFormat::LocalDateTime, TimePrecision::Milliseconds) => {
+            time.format("%Y-%m-%d %T%.3f").to_string()
+        }
+        (TimeFormat::SinceStart, precision) => format_duration(time - start_time, precision),
+        (TimeFormat::Ago, precision) => {
+            format!("{} ago", format_duration(crate::now() - time, precision))
+        }
+    }
+}
+
+fn format_duration(duration: chrono::TimeDelta, precision: TimePrecision) -> String {
+    let h = duration.num_hours() % 24;
+    let m = duration.num_minutes() % 60;
+    let s = duration.num_seconds() % 60;
+    match precision {
+        TimePrecision::Seconds => match (h, m, s) {
+            (0, 0, s) => format!("{s}s"),
+            (0, m, s) => format!("{m}m {s}s"),
+            (h, m, s) => format!("{h}h {m}m {s}s"),
+        },
+        TimePrecision::Milliseconds => {
+            let ms = duration.num_milliseconds() % 1000;
+            match (h, m, s, ms) {
+                (0, 0, 0, ms) => format!("{ms}ms"),
+                (0, 0, s, ms) => format!("{s}s {ms}ms"),
+                (0, m, s, ms) => format!("{m}m {s}s {ms}ms"),
+                (h, m, s, ms) => format!("{h}h {m}m {s}s {ms}ms"),
+            }
+        }
+    }
+}
+
+/// Returns the sequence number column, e.g. `#12 `, or nothing if it isn't included.
+pub(crate) fn format_seq(options: &FormatOptions, seq: u64, padding: Padding) -> String {
+    if options.include_seq {
+        format!("#{seq: >width$} ", width = padding.seq)
+    } else {
+        String::new()
+    }
+}
+
+/// Returns the time column, padded to its width.
+pub(crate) fn format_time_column(
+    options: &FormatOptions,
+    time: chrono::DateTime<chrono::Local>,
+    start_time: chrono::DateTime<chrono::Local>,
+    padding: Padding,
+) -> String {
+    format!(
+        "{: >width$} ",
+        format_time(time, options, start_time),
+        width = padding.time
+    )
+}
+
+/// Returns the time after the message with [`TimePosition::Trailing`], e.g. `  12:30:05`.
+pub(crate) fn format_trailing_time(
+    options: &FormatOptions,
+    time: chrono::DateTime<chrono::Local>,
+    start_time: chrono::DateTime<chrono::Local>,
+) -> String {
+    format!("  {}", format_time(time, options, start_time))
+}
+
+/// Returns the level, padded to the longest level label, e.g. `[WARN ]`.
+pub(crate) fn format_level(options: &FormatOptions, level: log::Level) -> String {
+    let (level, level_width) = match &options.level_label {
+        Some(level_label) => (
+            level_label(level),
+            log::Level::iter()
+                .map(|level| level_label(level).chars().count())
+                .max()
+                .unwrap_or_default(),
+        ),
+        None => (level.to_string(), 5),
+    };
+    let level = match options.level_align {
+        LevelAlign::Left => format!("{level: <level_width$}"),
+        #[cfg(feature = "ui")]
+        LevelAlign::Center => format!("{level: ^level_width$}"),
+        #[cfg(feature = "ui")]
+        LevelAlign::Right => format!("{level: >level_width$}"),
+    };
+    if options.level_brackets {
+        format!("[{level}]")
+    } else {
+        level
+    }
+}
+
+/// Returns the target padded to its column, followed by the separator.
+pub(crate) fn format_target(options: &FormatOptions, target: &str, padding: Padding) -> String {
+    format!(
+        "{target: <width$}{}",
+        options.target_separator,
+        width = padding.target
+    )
+}
+
+/// Returns the timestamp and the level/target column of a record.
+/// A [trailing](TimePosition::Trailing) timestamp is left out, [`format_text()`] adds it after the message.
+pub(crate) fn format_prefix(
+    options: &FormatOptions,
+    record: RecordParts<'_>,
+    start_time: chrono::DateTime<chrono::Local>,
+    padding: Padding,
+) -> (String, String) {
+    let mut date_str = format_seq(options, record.seq, padding);
+    if options.time && options.time_position == TimePosition::Leading {
+        date_str.push_str(&format_time_column(
+            options,
+            record.time,
+            start_time,
+            padding,
+        ));
+    }
+
+    let mut level_target = String::new();
+    if options.level {
+        level_target.push_str(&format_level(options, record.level));
+        level_target.push(' ');
+    }
+    if options.target {
+        level_target.push_str(&format_target(options, record.target, padding));
+    }
+
+    (date_str, level_target)
+}
+
+/// Returns the record as plain text, with the message only escaped if enabled.
+/// This is used for copying, searching and [`format_entry()`](crate::format_entry()).
+pub(crate) fn format_text(
+    options: &FormatOptions,
+    record: RecordParts<'_>,
+    start_time: chrono::DateTime<chrono::Local>,
+    padding: Padding,
+) -> String {
+    let (date_str, level_target) = format_prefix(options, record, start_time, padding);
+    let message = if options.escape_control_chars {
+        escape_control_chars(record.message)
+    } else {
+        Cow::Borrowed(record.message)
+    };
+    let mut text = format!("{date_str}{level_target}{message}");
+    if options.time && options.time_position == TimePosition::Trailing {
+        text.push_str(&format_trailing_time(options, record.time, start_time));
+    }
+    text
+}
+
+/// Replaces control characters except line breaks with their escaped form, e.g. `\r`.
+pub(crate) fn escape_control_chars(message: &str) -> Cow<'_, str> {
+    if !message.chars().any(|c| c.is_control() && c != '\n') {
+        return Cow::Borrowed(message);
+    }
+
+    let mut escaped = String::with_capacity(message.len());
+    for c in message.chars() {
+        match c {
+            '\n' => escaped.push(c),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.extend(c.escape_unicode()),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
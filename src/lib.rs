@@ -1,16 +1,67 @@
-#![doc = include_str!("../README.md")]
+// the README examples use the ui
+#![cfg_attr(feature = "ui", doc = include_str!("../README.md"))]
+#![allow(clippy::needless_doctest_main)]
+mod export;
+mod format;
+mod query;
+#[cfg(feature = "ui")]
+mod search;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+#[cfg(feature = "ui")]
 mod ui;
 
-use std::sync::LazyLock;
-use std::sync::Mutex;
+use std::collections::BTreeMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
 
+#[cfg(feature = "flate2")]
+pub use export::export_gz;
+pub use export::{export_logfmt, format_entry, write_to_file};
+pub use format::{FormatOptions, TimeFormat, TimePosition, TimePrecision};
 use hashbrown::HashMap;
+pub use query::{query, recent, Query};
+#[cfg(feature = "ui")]
+pub use search::SearchMode;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::EguiLoggerLayer;
+#[cfg(feature = "ui")]
 pub use ui::logger_ui;
+#[cfg(feature = "ui")]
+pub use ui::LoggerError;
+#[cfg(feature = "ui")]
 pub use ui::LoggerUi;
+#[cfg(feature = "ui")]
+pub use ui::LoggerUiConfig;
 
 use log::SetLoggerError;
 
-const LEVELS: [log::Level; log::Level::Trace as usize] = [
+/// Records with a target starting with this are always captured,
+/// regardless of the [max level](Builder::max_level), [target filters](Builder::filter_target)
+/// and [only_targets](Builder::only_targets).
+/// The prefix is removed from the target before storing the record.
+///
+/// ```
+/// log::info!(target: "force:my_app", "started with config {}", "app.toml");
+/// ```
+///
+/// The record still has to pass the global max level checked by the `log` macros,
+/// which [`Builder::init()`] sets to the max level of the logger.
+/// Enable [`Builder::allow_forced()`] if forced records can be below that.
+pub const FORCE_PREFIX: &str = "force:";
+
+/// The number of log levels.
+#[cfg(feature = "ui")]
+const LEVEL_COUNT: usize = 5;
+
+#[cfg(feature = "ui")]
+const LEVELS: [log::Level; LEVEL_COUNT] = [
     log::Level::Error,
     log::Level::Warn,
     log::Level::Info,
@@ -18,21 +69,130 @@ const LEVELS: [log::Level; log::Level::Trace as usize] = [
     log::Level::Trace,
 ];
 
+/// Returns all log levels, from the most to the least severe.
+#[cfg(feature = "ui")]
+fn all_levels() -> impl Iterator<Item = log::Level> {
+    LEVELS.into_iter()
+}
+
+/// Returns the index of the level in [`LEVELS`],
+/// used to index arrays with an entry per level.
+#[cfg(feature = "ui")]
+const fn level_index(level: log::Level) -> usize {
+    match level {
+        log::Level::Error => 0,
+        log::Level::Warn => 1,
+        log::Level::Info => 2,
+        log::Level::Debug => 3,
+        log::Level::Trace => 4,
+    }
+}
+
+// Makes sure `LEVELS` and `level_index` agree.
+#[cfg(feature = "ui")]
+const _: () = {
+    let mut i = 0;
+    while i < LEVEL_COUNT {
+        assert!(level_index(LEVELS[i]) == i);
+        i += 1;
+    }
+};
+
 /// The logger for egui
 /// You might want to use [`builder()`] instead.
 /// To get a builder with default values.
-pub struct EguiLogger;
+pub struct EguiLogger {
+    /// The max level as `LevelFilter as usize`, atomic so it can be changed at runtime.
+    max_level: AtomicUsize,
+    filters: Vec<(String, log::LevelFilter)>,
+    only_targets: Option<Vec<String>>,
+    rate_limit: Option<RateLimit>,
+    retain_for: Option<chrono::TimeDelta>,
+    also_stderr: bool,
+    allow_forced: bool,
+    store: Option<LogStore>,
+}
+
+/// Drops records identical to one logged within the window.
+struct RateLimit {
+    window: chrono::TimeDelta,
+    /// The last time a message was logged, keyed by the hash of its target and message.
+    recent: Mutex<HashMap<u64, chrono::DateTime<chrono::Local>>>,
+}
+
+impl RateLimit {
+    /// Messages are only pruned once there are more than this many.
+    const PRUNE_THRESHOLD: usize = 256;
+
+    fn new(window: Duration) -> Self {
+        Self {
+            window: chrono::TimeDelta::from_std(window).unwrap_or(chrono::TimeDelta::MAX),
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns true if the message should be logged.
+    fn check(&self, target: &str, message: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (target, message).hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let Ok(mut recent) = self.recent.lock() else {
+            return true;
+        };
+
+        let now = now();
+        if recent.len() > Self::PRUNE_THRESHOLD {
+            recent.retain(|_, time| now - *time < self.window);
+        }
+
+        match recent.get(&hash) {
+            Some(&time) if now - time < self.window => false,
+            _ => {
+                recent.insert(hash, now);
+                true
+            }
+        }
+    }
+}
+
+impl EguiLogger {
+    /// Returns the level filter for the given target.
+    /// The filter with the longest matching target prefix wins,
+    /// otherwise the max level is used.
+    fn level_for(&self, target: &str) -> log::LevelFilter {
+        self.filters
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.base_level(), |&(_, level)| level)
+    }
+}
 
 /// The builder for the logger.
 /// You can use [`builder()`] to get an instance of this.
 pub struct Builder {
     max_level: log::LevelFilter,
+    filters: Vec<(String, log::LevelFilter)>,
+    only_targets: Option<Vec<String>>,
+    rate_limit: Option<Duration>,
+    retain_for: Option<Duration>,
+    also_stderr: bool,
+    allow_forced: bool,
+    store: Option<LogStore>,
 }
 
 impl Default for Builder {
     fn default() -> Self {
         Self {
             max_level: log::LevelFilter::Debug,
+            filters: Vec::new(),
+            only_targets: None,
+            rate_limit: None,
+            retain_for: None,
+            also_stderr: false,
+            allow_forced: false,
+            store: None,
         }
     }
 }
@@ -41,12 +201,27 @@ impl Builder {
     /// Returns the Logger.
     /// Useful if you want to add it to a multi-logger.
     /// See [here](https://github.com/RegenJacob/egui_logger/blob/main/examples/multi_log.rs) for an example.
+    ///
+    /// Unlike [`init()`](Self::init) this doesn't set the global max level,
+    /// records above it never reach the logger.
+    /// Pass [`EguiLogger::max_level()`] to the multi-logger to get the configured levels.
     pub fn build(self) -> EguiLogger {
-        EguiLogger
+        EguiLogger {
+            max_level: AtomicUsize::new(self.max_level as usize),
+            filters: self.filters,
+            only_targets: self.only_targets,
+            rate_limit: self.rate_limit.map(RateLimit::new),
+            retain_for: self.retain_for.map(|retain_for| {
+                chrono::TimeDelta::from_std(retain_for).unwrap_or(chrono::TimeDelta::MAX)
+            }),
+            also_stderr: self.also_stderr,
+            allow_forced: self.allow_forced,
+            store: self.store,
+        }
     }
 
-    /// Sets the max level for the logger
-    /// this only has an effect when calling [`init()`].
+    /// Sets the max level for the logger.
+    /// Records whose target doesn't match any [filter](Self::filter_target) are checked against this.
     ///
     /// Defaults to [Debug](`log::LevelFilter::Debug`).
     pub fn max_level(mut self, max_level: log::LevelFilter) -> Self {
@@ -54,40 +229,284 @@ impl Builder {
         self
     }
 
+    /// Sets the max level for all targets starting with `target_prefix`.
+    /// If multiple prefixes match a target the longest one is used,
+    /// similar to `env_logger`.
+    ///
+    /// The prefix is matched as plain text, so `hyper` also matches `hyperlocal`.
+    ///
+    /// ```rust
+    /// use log::LevelFilter;
+    ///
+    /// egui_logger::builder()
+    ///     .max_level(LevelFilter::Info)
+    ///     .filter_target("hyper", LevelFilter::Warn)
+    ///     .filter_target("my_crate", LevelFilter::Trace)
+    ///     .init()
+    ///     .unwrap();
+    /// ```
+    pub fn filter_target(mut self, target_prefix: &str, level: log::LevelFilter) -> Self {
+        if let Some((_, existing)) = self
+            .filters
+            .iter_mut()
+            .find(|(prefix, _)| prefix == target_prefix)
+        {
+            *existing = level;
+        } else {
+            self.filters.push((target_prefix.to_string(), level));
+        }
+        self
+    }
+
+    /// Only collects records whose target starts with one of the given prefixes,
+    /// everything else is dropped before it reaches the buffer.
+    ///
+    /// Unlike the Categories menu of the ui, this keeps the buffer free of records you never want to see.
+    /// By default all targets are collected.
+    pub fn only_targets(mut self, target_prefixes: &[&str]) -> Self {
+        self.only_targets = Some(target_prefixes.iter().map(ToString::to_string).collect());
+        self
+    }
+
+    /// Configures the levels from a `RUST_LOG` style filter string.
+    ///
+    /// The string is a comma separated list of directives:
+    /// - `level` sets the [max_level](Self::max_level), e.g. `warn`
+    /// - `target=level` is the same as [`filter_target`](Self::filter_target), e.g. `hyper=off`
+    /// - `target` enables everything for that target, same as `target=trace`
+    ///
+    /// Levels are case insensitive and can be one of `off`, `error`, `warn`, `info`, `debug` and `trace`.
+    /// Directives with an invalid level are ignored.
+    /// Targets are prefixes, so `hyper=off` also silences `hyperlocal`.
+    ///
+    /// ```rust
+    /// egui_logger::builder()
+    ///     .parse_filters(&std::env::var("RUST_LOG").unwrap_or_default())
+    ///     .init()
+    ///     .unwrap();
+    /// ```
+    pub fn parse_filters(mut self, filters: &str) -> Self {
+        for directive in filters.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    if let Ok(level) = level.trim().parse() {
+                        self = self.filter_target(target.trim(), level);
+                    }
+                }
+                None => match directive.parse() {
+                    Ok(level) => self.max_level = level,
+                    Err(_) => self = self.filter_target(directive, log::LevelFilter::Trace),
+                },
+            }
+        }
+        self
+    }
+
+    /// Drops records with the same target and message as one logged within `per_message`.
+    /// This keeps the log usable when something logs in a tight loop.
+    ///
+    /// Disabled by default.
+    pub fn rate_limit(mut self, per_message: Duration) -> Self {
+        self.rate_limit = Some(per_message);
+        self
+    }
+
+    /// Drops records older than `duration` whenever a new record is logged.
+    /// This is applied in addition to the max log length of the ui.
+    ///
+    /// Disabled by default.
+    pub fn retain_for(mut self, duration: Duration) -> Self {
+        self.retain_for = Some(duration);
+        self
+    }
+
+    /// Additionally writes every record to stderr.
+    /// This is simpler than combining loggers with `multi_log` during development.
+    ///
+    /// Defaults to false.
+    pub fn also_stderr(mut self, enable: bool) -> Self {
+        self.also_stderr = enable;
+        self
+    }
+
+    /// Lets [forced](FORCE_PREFIX) records of every level through,
+    /// by raising the [max level of the logger](EguiLogger::max_level) and so the global one to `Trace`.
+    /// All other records are still filtered by the logger,
+    /// but the `log` macros no longer skip them before formatting, which costs a little.
+    ///
+    /// ```rust
+    /// egui_logger::builder()
+    ///     .max_level(log::LevelFilter::Warn)
+    ///     .allow_forced(true)
+    ///     .init()
+    ///     .unwrap();
+    ///
+    /// log::info!(target: "force:my_app", "kept even though info is filtered out");
+    /// ```
+    ///
+    /// Defaults to false.
+    pub fn allow_forced(mut self, enable: bool) -> Self {
+        self.allow_forced = enable;
+        self
+    }
+
+    /// Sets the buffer the records are stored in, see [`LogStore`].
+    ///
+    /// Defaults to the global buffer.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
     /// Initializes the global logger.
     /// This should be called very early in the program.
     ///
-    /// The max level is the [max_level](Self::max_level) field.
+    /// The global max level is set to the highest of the [max_level](Self::max_level)
+    /// and the [target filters](Self::filter_target),
+    /// or to `Off` if the `capture` feature is disabled.
     pub fn init(self) -> Result<(), SetLoggerError> {
-        log::set_logger(&EguiLogger).map(|()| log::set_max_level(self.max_level))
+        self.build_and_init_boxed().map(|_| ())
+    }
+
+    /// Same as [`init()`](Self::init), but returns the installed logger,
+    /// e.g. to read its [max level](EguiLogger::max_level) later.
+    ///
+    /// The logger is stored in a static to get the `'static` reference [`log::set_logger()`] needs,
+    /// so it is never dropped. Only the logger of the first call is stored,
+    /// later calls fail anyway because a logger is set by then, so repeated calls don't use more memory.
+    pub fn build_and_init_boxed(self) -> Result<&'static EguiLogger, SetLoggerError> {
+        let logger = BUILT.get_or_init(|| self.build());
+        log::set_logger(logger)?;
+        log::set_max_level(logger.max_level());
+        let _ = INSTALLED.set(logger);
+        Ok(logger)
+    }
+}
+
+impl EguiLogger {
+    /// Returns the highest level the logger accepts, from its [max level](Builder::max_level)
+    /// and [target filters](Builder::filter_target), or `Off` if the `capture` feature is disabled.
+    /// This is `Trace` if [forced records are allowed](Builder::allow_forced).
+    ///
+    /// The global max level has to be at least this, otherwise records never reach the logger.
+    /// [`Builder::init()`] sets it, when using a multi-logger pass this to it instead.
+    pub fn max_level(&self) -> log::LevelFilter {
+        if cfg!(feature = "capture") && self.allow_forced {
+            log::LevelFilter::Trace
+        } else if cfg!(feature = "capture") {
+            self.filters
+                .iter()
+                .map(|&(_, level)| level)
+                .fold(self.base_level(), std::cmp::max)
+        } else {
+            log::LevelFilter::Off
+        }
+    }
+
+    /// Changes the [max level](Builder::max_level) at runtime,
+    /// e.g. to capture trace records only while debugging something.
+    ///
+    /// If this logger was installed with [`Builder::init()`] the global max level is updated too,
+    /// when using a multi-logger you have to raise it yourself.
+    pub fn set_max_level(&self, max_level: log::LevelFilter) {
+        self.max_level.store(max_level as usize, Ordering::Relaxed);
+        if installed_logger().is_some_and(|installed| std::ptr::eq(installed, self)) {
+            log::set_max_level(self.max_level());
+        }
+    }
+
+    /// The max level without the target filters.
+    pub(crate) fn base_level(&self) -> log::LevelFilter {
+        let max_level = self.max_level.load(Ordering::Relaxed);
+        log::LevelFilter::iter()
+            .nth(max_level)
+            .unwrap_or(log::LevelFilter::Trace)
     }
 }
 
+/// The logger built by the first [`Builder::init()`], whether installing it succeeded or not.
+static BUILT: OnceLock<EguiLogger> = OnceLock::new();
+
+/// The logger installed by [`Builder::init()`].
+static INSTALLED: OnceLock<&'static EguiLogger> = OnceLock::new();
+
+/// Returns the logger installed by [`Builder::init()`], if any.
+pub(crate) fn installed_logger() -> Option<&'static EguiLogger> {
+    INSTALLED.get().copied()
+}
+
 impl log::Log for EguiLogger {
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        metadata.level() <= log::STATIC_MAX_LEVEL
+        if metadata.target().starts_with(FORCE_PREFIX) {
+            return cfg!(feature = "capture");
+        }
+
+        cfg!(feature = "capture")
+            && metadata.level() <= log::STATIC_MAX_LEVEL
+            && metadata.level() <= self.level_for(metadata.target())
+            && self.only_targets.as_ref().is_none_or(|only_targets| {
+                only_targets
+                    .iter()
+                    .any(|prefix| metadata.target().starts_with(prefix.as_str()))
+            })
     }
 
     fn log(&self, record: &log::Record) {
         if self.enabled(record.metadata()) {
-            if let Ok(ref mut logger) = LOGGER.lock() {
-                logger.logs.push(Record {
-                    level: record.level(),
-                    message: record.args().to_string(),
-                    target: record.target().to_string(),
-                    time: chrono::Local::now(),
-                });
-
-                if !logger.categories.contains_key(record.target()) {
-                    logger.categories.insert(record.target().to_string(), true);
-                    logger.max_category_length =
-                        logger.max_category_length.max(record.target().len());
+            let message = record.args().to_string();
+            let target = record
+                .target()
+                .strip_prefix(FORCE_PREFIX)
+                .unwrap_or(record.target());
+
+            if let Some(rate_limit) = &self.rate_limit {
+                if !rate_limit.check(target, &message) {
+                    return;
                 }
             }
+
+            if self.also_stderr {
+                let mut stderr = std::io::stderr().lock();
+                let _ = writeln!(
+                    stderr,
+                    "{} [{:5}] {}: {}",
+                    now().format("%T%.3f"),
+                    record.level(),
+                    target,
+                    message
+                );
+                let _ = stderr.flush();
+            }
+
+            let location = record
+                .file()
+                .zip(record.line())
+                .map(|(file, line)| format!("{file}:{line}"));
+
+            let mut logger = match &self.store {
+                Some(store) => store.lock(),
+                None => lock_logger(),
+            };
+            logger.push(record.level(), target, message, location);
+
+            if let Some(retain_for) = self.retain_for {
+                logger.drop_older_than(retain_for);
+            }
         }
     }
 
-    fn flush(&self) {}
+    /// Records are stored synchronously in [`log()`](log::Log::log), so nothing is queued here.
+    /// Only the [stderr output](Builder::also_stderr) is flushed if it's enabled,
+    /// otherwise this is a no-op.
+    fn flush(&self) {
+        if self.also_stderr {
+            let _ = std::io::stderr().flush();
+        }
+    }
 }
 
 /// Initializes the global logger.
@@ -115,27 +534,568 @@ pub fn init_with_max_level(max_level: log::LevelFilter) -> Result<(), SetLoggerE
     builder().max_level(max_level).init()
 }
 
+/// Inserts a record directly into the log buffer, bypassing the [`log`] macros
+/// and any level filtering.
+///
+/// Useful for forwarding logs from non-`log` sources or populating the ui in tests.
+/// Does nothing if the `capture` feature is disabled.
+pub fn push_record(level: log::Level, target: &str, message: impl Into<String>) {
+    if cfg!(feature = "capture") {
+        lock_logger().push(level, target, message.into(), None);
+    }
+}
+
+/// A log record taken out of the buffer, see [`take_logs()`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogEntry {
+    /// The position of the record in the order it was logged.
+    pub seq: u64,
+    pub level: log::Level,
+    pub target: String,
+    pub message: String,
+    pub time: chrono::DateTime<chrono::Local>,
+    /// The source location as `file:line`, if it is known.
+    pub location: Option<String>,
+}
+
+impl From<Record> for LogEntry {
+    fn from(record: Record) -> Self {
+        Self {
+            seq: record.seq,
+            level: record.level,
+            target: record.target,
+            message: record.message.to_string(),
+            time: record.time,
+            location: record.location,
+        }
+    }
+}
+
+impl From<&Record> for LogEntry {
+    fn from(record: &Record) -> Self {
+        Self {
+            seq: record.seq,
+            level: record.level,
+            target: record.target.clone(),
+            message: record.message.to_string(),
+            time: record.time,
+            location: record.location.clone(),
+        }
+    }
+}
+
+/// Which records are dropped when the buffer exceeds its [capacity](set_capacity)
+/// or the max log length of the ui, see [`set_retention()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Retention {
+    /// Drops the oldest records.
+    #[default]
+    DropOldest,
+    /// Once the buffer is full, keeps the first `head` records, e.g. the startup logs,
+    /// and the `tail` most recent ones.
+    /// The records in between are dropped and shown as a "… N logs omitted …" marker.
+    /// Nothing is dropped until the buffer is full for the first time,
+    /// from then on it holds `head + tail` records until it is cleared.
+    ///
+    /// Both are limited by the capacity, the head is kept first.
+    HeadTail { head: usize, tail: usize },
+}
+
+/// Sets which records are dropped when the buffer is full.
+/// Defaults to [`Retention::DropOldest`].
+pub fn set_retention(retention: Retention) {
+    let mut logger = lock_logger();
+    logger.retention = retention;
+    if let Some(capacity) = logger.capacity {
+        logger.truncate(capacity);
+    }
+}
+
+/// Limits the number of records kept in the buffer.
+/// The oldest records are dropped as soon as new ones are logged,
+/// so memory stays bounded even if no ui is shown.
+///
+/// Every `LoggerUi` additionally trims the buffer to its own max log length,
+/// so effectively the smaller of both limits applies.
+/// `None` removes the limit, which is the default.
+pub fn set_capacity(capacity: Option<usize>) {
+    let mut logger = lock_logger();
+    logger.capacity = capacity;
+    if let Some(capacity) = capacity {
+        logger.truncate(capacity);
+    }
+}
+
+/// Returns the number of records currently in the buffer.
+pub fn len() -> usize {
+    lock_logger().logs.len()
+}
+
+/// Returns a copy of the records in `start..end`, oldest first.
+/// The range is clamped to the records in the buffer.
+///
+/// Together with [`len()`] this can be used to page through the log in a custom ui.
+pub fn get_range(start: usize, end: usize) -> Vec<LogEntry> {
+    let logger = lock_logger();
+    let end = end.min(logger.logs.len());
+    let start = start.min(end);
+    logger.logs[start..end].iter().map(LogEntry::from).collect()
+}
+
+/// Removes all records from the buffer, like the Clear button of the ui.
+///
+/// The categories are kept, so their enabled state and the width of the target column stay.
+/// Use [`clear_all()`] to reset them too.
+pub fn clear_logs() {
+    lock_logger().clear();
+}
+
+/// Removes all records and categories from the buffer, for a clean slate.
+///
+/// Unlike [`clear_logs()`] this also forgets which categories were disabled,
+/// and the target column shrinks to the targets logged afterwards.
+pub fn clear_all() {
+    let mut logger = lock_logger();
+    logger.clear();
+    logger.categories.clear();
+    logger.max_category_length = 0;
+    logger.categories_generation += 1;
+}
+
+/// Removes all records from the buffer and returns them, oldest first.
+///
+/// Useful to write the log to a report on shutdown, e.g. in a panic hook.
+pub fn take_logs() -> Vec<LogEntry> {
+    lock_logger().logs.drain(..).map(LogEntry::from).collect()
+}
+
+/// Returns a copy of all records in the buffer, oldest first.
+///
+/// Together with [`restore()`] and the `serde` feature this can be used to persist the log.
+pub fn snapshot() -> Vec<LogEntry> {
+    lock_logger().logs.iter().map(LogEntry::from).collect()
+}
+
+/// Replaces all records in the buffer with `entries`, e.g. from a [`snapshot()`].
+///
+/// The categories are rebuilt from the targets of the entries,
+/// categories that existed before keep their enabled state.
+pub fn restore(entries: Vec<LogEntry>) {
+    let mut logger = lock_logger();
+    let logger = &mut *logger;
+
+    let mut logs: Vec<Record> = entries
+        .into_iter()
+        .map(|entry| Record {
+            seq: entry.seq,
+            level: entry.level,
+            message: entry.message.into(),
+            target: entry.target,
+            time: entry.time,
+            location: entry.location,
+        })
+        .collect();
+    logs.sort_by_key(|record| record.seq);
+    if let Some(last) = logs.last() {
+        logger.next_seq = logger.next_seq.max(last.seq + 1);
+    }
+    logger.logs = logs;
+
+    let old_categories = std::mem::take(&mut logger.categories);
+    logger.max_category_length = 0;
+    for record in &logger.logs {
+        if !logger.categories.contains_key(&record.target) {
+            let enabled = old_categories.get(&record.target).copied().unwrap_or(true);
+            logger.categories.insert(record.target.clone(), enabled);
+            logger.max_category_length = logger.max_category_length.max(record.target.len());
+        }
+    }
+    logger.categories_generation += 1;
+
+    if let Some(capacity) = logger.capacity {
+        logger.truncate(capacity);
+    }
+}
+
 struct Record {
+    seq: u64,
     level: log::Level,
-    message: String,
+    /// Shared, so the ui can hold on to it without copying the text.
+    message: Arc<str>,
     target: String,
     time: chrono::DateTime<chrono::Local>,
+    location: Option<String>,
 }
 
 struct Logger {
     logs: Vec<Record>,
-    categories: HashMap<String, bool>,
+    /// The enabled state of each category, sorted by name.
+    categories: BTreeMap<String, bool>,
     max_category_length: usize,
+    dropped_count: usize,
+    /// The maximum number of records kept, see [`set_capacity()`].
+    capacity: Option<usize>,
+    retention: Retention,
+    /// The seq of the last head record and how many records after it have been omitted,
+    /// see [`Retention::HeadTail`].
+    omitted: Option<(u64, usize)>,
+    next_seq: u64,
+    /// The number of errors logged, including dropped ones.
+    error_count: usize,
+    /// Incremented whenever categories are enabled or disabled,
+    /// or the records are replaced by [`restore()`].
+    categories_generation: u64,
     start_time: chrono::DateTime<chrono::Local>,
 }
-static LOGGER: LazyLock<Mutex<Logger>> = LazyLock::new(|| {
-    Mutex::new(Logger {
-        logs: Vec::new(),
-        categories: HashMap::new(),
-        max_category_length: 0,
-        start_time: chrono::Local::now(),
-    })
-});
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self {
+            logs: Vec::new(),
+            categories: BTreeMap::new(),
+            max_category_length: 0,
+            dropped_count: 0,
+            capacity: None,
+            retention: Retention::default(),
+            omitted: None,
+            next_seq: 0,
+            error_count: 0,
+            categories_generation: 0,
+            start_time: now(),
+        }
+    }
+}
+
+impl Logger {
+    fn enable_category(&mut self, pattern: &str, enabled: bool) {
+        self.update_categories(|category, category_enabled| {
+            if category_matches(pattern, category) {
+                enabled
+            } else {
+                category_enabled
+            }
+        });
+    }
+
+    /// Sets the enabled state of each category to what `enabled` returns for it,
+    /// so the filters of every ui showing the log are rebuilt.
+    fn update_categories(&mut self, mut enabled: impl FnMut(&str, bool) -> bool) {
+        for (category, category_enabled) in self.categories.iter_mut() {
+            *category_enabled = enabled(category, *category_enabled);
+        }
+        self.categories_generation += 1;
+    }
+
+    /// Returns the record with the sequence number, if it is still in the log.
+    #[cfg(feature = "ui")]
+    fn record(&self, seq: u64) -> Option<&Record> {
+        self.logs
+            .binary_search_by_key(&seq, |record| record.seq)
+            .ok()
+            .map(|index| &self.logs[index])
+    }
+
+    /// Drops all records older than `max_age`.
+    fn drop_older_than(&mut self, max_age: chrono::TimeDelta) {
+        let now = now();
+        let dropped_entries = self
+            .logs
+            .partition_point(|record| now - record.time > max_age);
+        drop(self.logs.drain(..dropped_entries));
+        self.dropped_count += dropped_entries;
+    }
+
+    fn push(&mut self, level: log::Level, target: &str, message: String, location: Option<String>) {
+        if level == log::Level::Error {
+            NEW_ERRORS.store(true, Ordering::Relaxed);
+            self.error_count += 1;
+        }
+
+        self.logs.push(Record {
+            location,
+            seq: self.next_seq,
+            level,
+            message: message.into(),
+            target: target.to_string(),
+            time: now(),
+        });
+        self.next_seq += 1;
+
+        self.register_category(target);
+
+        if let Some(capacity) = self.capacity {
+            self.truncate(capacity);
+        }
+    }
+
+    fn register_category(&mut self, target: &str) {
+        if !self.categories.contains_key(target) {
+            self.categories.insert(target.to_string(), true);
+            self.max_category_length = self.max_category_length.max(target.len());
+        }
+    }
+
+    /// Returns the seq of the last head record and how many records after it have been omitted,
+    /// if records have been omitted since the head was last dropped, e.g. by clearing the log.
+    fn omitted(&self) -> Option<(u64, usize)> {
+        self.omitted.filter(|&(head_end, _)| {
+            self.logs
+                .binary_search_by_key(&head_end, |record| record.seq)
+                .is_ok()
+        })
+    }
+
+    /// Removes all records, the head of [`Retention::HeadTail`] starts over with the next ones.
+    fn clear(&mut self) {
+        self.logs.clear();
+        self.omitted = None;
+    }
+
+    /// Drops records according to the [retention](Retention) so at most `len` records are left.
+    fn truncate(&mut self, len: usize) {
+        // once records were omitted, only the head and tail are kept
+        if self.logs.len() <= len && self.omitted().is_none() {
+            return;
+        }
+        let Retention::HeadTail { head, tail } = self.retention else {
+            let dropped_entries = self.logs.len().saturating_sub(len);
+            drop(self.logs.drain(..dropped_entries));
+            self.dropped_count += dropped_entries;
+            return;
+        };
+
+        // records that are already in the head stay there
+        let head = match self.omitted() {
+            Some((head_end, _)) => self.logs.partition_point(|record| record.seq <= head_end),
+            None => head,
+        }
+        .min(len);
+        let tail = tail.min(len - head);
+        let dropped_entries = self.logs.len().saturating_sub(head + tail);
+        if dropped_entries == 0 {
+            return;
+        }
+        drop(self.logs.drain(head..head + dropped_entries));
+        self.dropped_count += dropped_entries;
+
+        self.omitted = match head.checked_sub(1) {
+            Some(head_end) => {
+                let omitted = self.omitted().map_or(0, |(_, omitted)| omitted);
+                Some((self.logs[head_end].seq, omitted + dropped_entries))
+            }
+            None => None,
+        };
+    }
+}
+
+/// A log buffer.
+///
+/// The records of the [`log`] macros go to a global buffer by default.
+/// Create your own to keep the logs of independent subsystems apart,
+/// then log into it with [`Builder::store()`] or [`LogStore::push_record()`]
+/// and show it with `LoggerUi::with_store()`.
+///
+/// Cloning it is cheap, the clones share the same buffer.
+#[derive(Clone, Default)]
+pub struct LogStore {
+    logger: Arc<Mutex<Logger>>,
+}
+
+impl LogStore {
+    /// Creates an empty log buffer, independent of the global one.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a record directly into this buffer, see [`push_record()`].
+    pub fn push_record(&self, level: log::Level, target: &str, message: impl Into<String>) {
+        if cfg!(feature = "capture") {
+            self.lock().push(level, target, message.into(), None);
+        }
+    }
+
+    /// Returns the number of records in this buffer.
+    pub fn len(&self) -> usize {
+        self.lock().logs.len()
+    }
+
+    /// Returns true if there are no records in this buffer.
+    pub fn is_empty(&self) -> bool {
+        self.lock().logs.is_empty()
+    }
+
+    /// Returns a copy of all records in this buffer, oldest first.
+    pub fn snapshot(&self) -> Vec<LogEntry> {
+        self.lock().logs.iter().map(LogEntry::from).collect()
+    }
+
+    /// Locks the buffer.
+    ///
+    /// If a thread panicked while holding the lock, the poison is cleared
+    /// so logging keeps working instead of dropping all further records.
+    fn lock(&self) -> MutexGuard<'_, Logger> {
+        self.logger.lock().unwrap_or_else(|poisoned| {
+            self.logger.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Locks the buffer, giving up after about `timeout_ms` milliseconds.
+    fn try_lock(&self, timeout_ms: u32) -> Option<MutexGuard<'_, Logger>> {
+        for _ in 0..timeout_ms {
+            match self.logger.try_lock() {
+                Ok(logger) => return Some(logger),
+                Err(TryLockError::Poisoned(poisoned)) => {
+                    self.logger.clear_poison();
+                    return Some(poisoned.into_inner());
+                }
+                // there are no other threads that could release the lock on the web
+                #[cfg(target_arch = "wasm32")]
+                Err(TryLockError::WouldBlock) => return None,
+                #[cfg(not(target_arch = "wasm32"))]
+                Err(TryLockError::WouldBlock) => std::thread::sleep(Duration::from_millis(1)),
+            }
+        }
+        None
+    }
+}
+
+static LOGGER: LazyLock<LogStore> = LazyLock::new(LogStore::new);
+
+/// Locks the global log buffer.
+fn lock_logger() -> MutexGuard<'static, Logger> {
+    LOGGER.lock()
+}
+
+/// Adds a category before anything has been logged to it,
+/// so it already shows up in the Categories menu and can be enabled or disabled.
+pub fn register_category(target: &str) {
+    lock_logger().register_category(target);
+}
+
+/// Enables or disables all categories matching the pattern.
+///
+/// The pattern is matched case-insensitively and `*` matches any sequence of characters,
+/// e.g. `egui_glow::*` matches all modules of `egui_glow`.
+/// Without a `*` the category has to match exactly.
+///
+/// Only categories that have already been logged to or were [registered](register_category) are affected.
+pub fn enable_category(pattern: &str, enabled: bool) {
+    lock_logger().enable_category(pattern, enabled);
+}
+
+/// Sets the enabled state of many categories at once, e.g. to restore saved settings.
+///
+/// Categories that haven't been logged to yet are registered, all other categories are left as they are.
+pub fn set_categories(visible: &std::collections::HashMap<String, bool>) {
+    let mut logger = lock_logger();
+    for (category, &enabled) in visible {
+        logger.register_category(category);
+        logger.categories.insert(category.clone(), enabled);
+    }
+    logger.categories_generation += 1;
+}
+
+/// Enables or disables all categories, like Select All and Unselect All in the Categories menu.
+pub fn set_all_categories(enabled: bool) {
+    lock_logger().update_categories(|_, _| enabled);
+}
+
+/// Returns true if the category matches the wildcard pattern, ignoring case.
+fn category_matches(pattern: &str, category: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let category = category.to_lowercase();
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = category.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // no wildcard
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+/// Installs a panic hook that logs panics as errors with the target `panic`,
+/// then calls the previously installed hook.
+///
+/// Panics bypass [`log`], so without this they never show up in the log.
+/// If the log buffer stays locked, e.g. because the panicking thread holds it, the panic isn't logged.
+pub fn install_panic_hook() {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line()));
+
+        // a panic while the buffer is locked must not deadlock the hook
+        let logger = cfg!(feature = "capture")
+            .then(|| LOGGER.try_lock(100))
+            .flatten();
+        if let Some(mut logger) = logger {
+            logger.push(log::Level::Error, "panic", message.to_string(), location);
+        }
+
+        previous_hook(info);
+    }));
+}
+
+/// Set when an error is logged, cleared by [`has_new_errors()`].
+static NEW_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Returns the current time, or the time set by `testing::freeze_now()`.
+fn now() -> chrono::DateTime<chrono::Local> {
+    #[cfg(feature = "testing")]
+    if let Some(now) = testing::frozen_now() {
+        return now;
+    }
+    chrono::Local::now()
+}
+
+/// Returns true if an error has been logged since the last call.
+///
+/// This is cheap enough to be polled every frame, e.g. to show an error badge.
+pub fn has_new_errors() -> bool {
+    NEW_ERRORS.swap(false, Ordering::Relaxed)
+}
+
+/// Returns how many records have been dropped because the log exceeded the [capacity](set_capacity),
+/// the max log length of the ui or the [retention time](Builder::retain_for),
+/// or to keep only the head and tail with [`Retention::HeadTail`].
+pub fn dropped_count() -> usize {
+    lock_logger().dropped_count
+}
+
+/// Returns the time the "Since Start" timestamps are relative to.
+/// This is the time the logger was first used, unless it was [reset](reset_start_time).
+pub fn start_time() -> chrono::DateTime<chrono::Local> {
+    lock_logger().start_time
+}
+
+/// Sets the start time to now.
+/// "Since Start" timestamps will be relative to this point.
+pub fn reset_start_time() {
+    lock_logger().start_time = now();
+}
 
 /**
 This returns the Log builder with default values.
@@ -162,3 +1122,203 @@ fn main() {
 pub fn builder() -> Builder {
     Builder::default()
 }
+
+#[cfg(all(test, feature = "capture"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logging_continues_after_a_panic_poisoned_the_buffer() {
+        let _ = std::thread::spawn(|| {
+            let _logger = LOGGER.lock();
+            panic!("poisoning the log buffer");
+        })
+        .join();
+        assert!(LOGGER.logger.is_poisoned());
+
+        let len_before = len();
+        push_record(log::Level::Warn, "poison_test", "still logging");
+        assert_eq!(len(), len_before + 1);
+        assert!(!LOGGER.logger.is_poisoned());
+    }
+
+    fn head_tail_logger(capacity: usize, head: usize, tail: usize) -> Logger {
+        Logger {
+            capacity: Some(capacity),
+            retention: Retention::HeadTail { head, tail },
+            ..Logger::default()
+        }
+    }
+
+    fn push_records(logger: &mut Logger, count: usize) {
+        for _ in 0..count {
+            logger.push(log::Level::Info, "retention_test", String::new(), None);
+        }
+    }
+
+    fn seqs(logger: &Logger) -> Vec<u64> {
+        logger.logs.iter().map(|record| record.seq).collect()
+    }
+
+    #[test]
+    fn head_tail_keeps_everything_below_the_capacity() {
+        let mut logger = head_tail_logger(10, 3, 4);
+        push_records(&mut logger, 10);
+        assert_eq!(seqs(&logger), (0..10).collect::<Vec<_>>());
+        assert_eq!(logger.omitted(), None);
+        assert_eq!(logger.dropped_count, 0);
+    }
+
+    #[test]
+    fn head_tail_keeps_the_head_and_tail_after_overflowing() {
+        let mut logger = head_tail_logger(10, 3, 4);
+        push_records(&mut logger, 11);
+        assert_eq!(seqs(&logger), [0, 1, 2, 7, 8, 9, 10]);
+        assert_eq!(logger.omitted(), Some((2, 4)));
+
+        for _ in 0..20 {
+            push_records(&mut logger, 1);
+            assert_eq!(logger.logs.len(), 7);
+        }
+        assert_eq!(seqs(&logger), [0, 1, 2, 27, 28, 29, 30]);
+        assert_eq!(logger.omitted(), Some((2, 24)));
+        assert_eq!(logger.dropped_count, 24);
+    }
+
+    #[test]
+    fn head_tail_head_is_limited_by_the_capacity() {
+        let mut logger = head_tail_logger(5, 8, 2);
+        push_records(&mut logger, 7);
+        assert_eq!(seqs(&logger), [0, 1, 2, 3, 4]);
+        assert_eq!(logger.omitted(), Some((4, 2)));
+    }
+
+    #[test]
+    fn parse_filters_reads_the_max_level_and_targets() {
+        let logger = builder()
+            .parse_filters("warn,my_crate=debug,hyper=off")
+            .build();
+        assert_eq!(logger.level_for("other"), log::LevelFilter::Warn);
+        assert_eq!(logger.level_for("my_crate::net"), log::LevelFilter::Debug);
+        assert_eq!(logger.level_for("hyper"), log::LevelFilter::Off);
+    }
+
+    #[test]
+    fn parse_filters_enables_everything_for_a_bare_target() {
+        let logger = builder().parse_filters("info, my_crate").build();
+        assert_eq!(logger.level_for("my_crate"), log::LevelFilter::Trace);
+        assert_eq!(logger.level_for("other"), log::LevelFilter::Info);
+    }
+
+    #[test]
+    fn parse_filters_ignores_invalid_levels_and_empty_segments() {
+        let logger = builder()
+            .max_level(log::LevelFilter::Info)
+            .parse_filters(",,hyper=loud, ,my_crate=DEBUG,")
+            .build();
+        assert_eq!(logger.level_for("hyper"), log::LevelFilter::Info);
+        assert_eq!(logger.level_for("my_crate"), log::LevelFilter::Debug);
+        assert_eq!(logger.filters.len(), 1);
+    }
+
+    #[test]
+    fn level_for_uses_the_longest_matching_prefix() {
+        let logger = builder()
+            .max_level(log::LevelFilter::Info)
+            .filter_target("my_crate::net::http", log::LevelFilter::Trace)
+            .filter_target("my_crate", log::LevelFilter::Warn)
+            .filter_target("my_crate::net", log::LevelFilter::Error)
+            .build();
+        assert_eq!(logger.level_for("my_crate::ui"), log::LevelFilter::Warn);
+        assert_eq!(
+            logger.level_for("my_crate::net::tcp"),
+            log::LevelFilter::Error
+        );
+        assert_eq!(
+            logger.level_for("my_crate::net::http::client"),
+            log::LevelFilter::Trace
+        );
+        assert_eq!(logger.level_for("other"), log::LevelFilter::Info);
+    }
+
+    #[test]
+    fn level_for_matches_plain_prefixes() {
+        let logger = builder()
+            .filter_target("hyper", log::LevelFilter::Off)
+            .build();
+        assert_eq!(logger.level_for("hyper::client"), log::LevelFilter::Off);
+        assert_eq!(logger.level_for("hyperlocal"), log::LevelFilter::Off);
+    }
+
+    #[test]
+    fn category_matches_without_a_wildcard() {
+        assert!(category_matches("egui_glow", "egui_glow"));
+        assert!(!category_matches("egui_glow", "egui_glow::painter"));
+        assert!(!category_matches("egui_glow::painter", "egui_glow"));
+    }
+
+    #[test]
+    fn category_matches_a_trailing_wildcard() {
+        assert!(category_matches("egui_glow::*", "egui_glow::painter"));
+        assert!(category_matches("egui_glow::*", "egui_glow::"));
+        assert!(!category_matches("egui_glow::*", "egui_glow"));
+        assert!(category_matches("*", "anything"));
+    }
+
+    #[test]
+    fn category_matches_a_middle_wildcard() {
+        assert!(category_matches("my_app::*::db", "my_app::net::db"));
+        assert!(category_matches("my_app::*::db", "my_app::net::pool::db"));
+        assert!(!category_matches("my_app::*::db", "my_app::net::dbx"));
+        assert!(!category_matches("my_app::*::db", "my_app::db"));
+    }
+
+    #[test]
+    fn category_matches_overlapping_parts() {
+        assert!(!category_matches("*ab*b", "ab"));
+        assert!(category_matches("*ab*b", "abb"));
+        assert!(!category_matches("a*a", "a"));
+        assert!(category_matches("a*a", "aa"));
+    }
+
+    #[test]
+    fn category_matches_ignores_case() {
+        assert!(category_matches("EGUI_glow::*", "egui_GLOW::Painter"));
+    }
+
+    #[test]
+    fn allow_forced_lets_forced_records_below_the_max_level_through() {
+        use log::Log;
+
+        let metadata = |target| {
+            log::Metadata::builder()
+                .level(log::Level::Debug)
+                .target(target)
+                .build()
+        };
+        let logger = builder()
+            .max_level(log::LevelFilter::Warn)
+            .allow_forced(true)
+            .build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Trace);
+        assert!(logger.enabled(&metadata("force:my_app")));
+        assert!(!logger.enabled(&metadata("my_app")));
+
+        let logger = builder().max_level(log::LevelFilter::Warn).build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Warn);
+    }
+
+    #[test]
+    fn clearing_starts_a_new_head() {
+        let mut logger = head_tail_logger(10, 3, 4);
+        push_records(&mut logger, 11);
+        logger.clear();
+        assert_eq!(logger.omitted(), None);
+
+        push_records(&mut logger, 10);
+        assert_eq!(seqs(&logger), (11..21).collect::<Vec<_>>());
+        push_records(&mut logger, 1);
+        assert_eq!(seqs(&logger), [11, 12, 13, 18, 19, 20, 21]);
+        assert_eq!(logger.omitted(), Some((13, 4)));
+    }
+}
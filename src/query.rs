@@ -0,0 +1,116 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::{lock_logger, LogEntry, Record};
+
+/// The filters for [`query()`], all of them have to match.
+///
+/// ```rust
+/// use egui_logger::Query;
+///
+/// let recent_problems = egui_logger::query(
+///     Query::new()
+///         .levels(log::Level::Error..=log::Level::Warn)
+///         .target("my_app::net")
+///         .since(chrono::Local::now() - chrono::TimeDelta::minutes(5)),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query {
+    levels: (Bound<log::Level>, Bound<log::Level>),
+    target: Option<String>,
+    since: Option<chrono::DateTime<chrono::Local>>,
+    until: Option<chrono::DateTime<chrono::Local>>,
+    text: Option<String>,
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self {
+            levels: (Bound::Unbounded, Bound::Unbounded),
+            target: None,
+            since: None,
+            until: None,
+            text: None,
+        }
+    }
+}
+
+impl Query {
+    /// Creates a query that matches all records.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches records with a level in `levels`, e.g. `log::Level::Error..=log::Level::Warn`.
+    /// Levels are ordered from `Error` to `Trace`.
+    pub fn levels(mut self, levels: impl RangeBounds<log::Level>) -> Self {
+        self.levels = (levels.start_bound().cloned(), levels.end_bound().cloned());
+        self
+    }
+
+    /// Only matches records whose target contains `target`.
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Only matches records logged at or after `time`.
+    pub fn since(mut self, time: chrono::DateTime<chrono::Local>) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Only matches records logged before `time`.
+    pub fn until(mut self, time: chrono::DateTime<chrono::Local>) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Only matches records whose message contains `text`, ignoring case.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into().to_lowercase());
+        self
+    }
+
+    fn matches(&self, record: &Record) -> bool {
+        self.levels.contains(&record.level)
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|target| record.target.contains(target.as_str()))
+            && self.since.is_none_or(|since| record.time >= since)
+            && self.until.is_none_or(|until| record.time < until)
+            && self
+                .text
+                .as_ref()
+                .is_none_or(|text| record.message.to_lowercase().contains(text.as_str()))
+    }
+}
+
+/// Returns a copy of the records in the buffer that match the query, oldest first.
+///
+/// This is the programmatic counterpart to the filters of the ui,
+/// e.g. for assertions or dashboards.
+pub fn query(query: Query) -> Vec<LogEntry> {
+    lock_logger()
+        .logs
+        .iter()
+        .filter(|record| query.matches(record))
+        .map(LogEntry::from)
+        .collect()
+}
+
+/// Returns a copy of the `n` most recent records at or above `level`, newest first,
+/// e.g. `LevelFilter::Warn` returns warnings and errors.
+///
+/// Only the returned records are copied, so this is cheap enough for e.g. a "recent errors" tooltip.
+pub fn recent(level: log::LevelFilter, n: usize) -> Vec<LogEntry> {
+    lock_logger()
+        .logs
+        .iter()
+        .rev()
+        .filter(|record| record.level <= level)
+        .take(n)
+        .map(LogEntry::from)
+        .collect()
+}
@@ -0,0 +1,241 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::Record;
+
+/// Limits the memory a compiled search regex may use,
+/// so huge patterns fail to compile instead of stalling the ui.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+const REGEX_DFA_SIZE_LIMIT: usize = 1 << 20;
+
+/// How the search term is matched against the records.
+/// See [`LoggerUi::search_mode()`](crate::LoggerUi::search_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SearchMode {
+    /// The records contain the search term.
+    #[default]
+    Plain,
+    /// The search term is a regex.
+    Regex,
+    /// The records contain the characters of the search term in order, but not necessarily next to each other,
+    /// e.g. `cnfail` matches `connection failed`.
+    Fuzzy,
+}
+
+/// A single search term.
+/// Plain terms are compiled to a literal regex as well,
+/// so case-insensitive matching doesn't allocate a lowercase copy of every record.
+struct SearchTerm {
+    term: String,
+    regex: Regex,
+}
+
+/// A parsed search query.
+///
+/// Words prefixed with `target:` or `message:` only match against that field of a record,
+/// all other words are matched against the whole line.
+#[derive(Default)]
+pub(crate) struct SearchQuery {
+    text: Option<SearchTerm>,
+    target: Option<SearchTerm>,
+    message: Option<SearchTerm>,
+    case_sensitive: bool,
+    mode: SearchMode,
+}
+
+impl SearchQuery {
+    /// Parses the search term.
+    /// Returns an error if regex search is used and a term isn't a valid regex.
+    pub(crate) fn new(
+        search_term: &str,
+        case_sensitive: bool,
+        mode: SearchMode,
+    ) -> Result<Self, regex::Error> {
+        let mut query = Self {
+            case_sensitive,
+            mode,
+            ..Default::default()
+        };
+
+        let mut text = Vec::new();
+        let mut qualified = false;
+        for word in search_term.split_whitespace() {
+            if let Some(target) = word.strip_prefix("target:") {
+                query.target = query.term(target)?;
+                qualified = true;
+            } else if let Some(message) = word.strip_prefix("message:") {
+                query.message = query.term(message)?;
+                qualified = true;
+            } else {
+                text.push(word);
+            }
+        }
+
+        // without qualifiers the search term is used as is, including its whitespace
+        query.text = if qualified {
+            query.term(&text.join(" "))?
+        } else {
+            query.term(search_term)?
+        };
+
+        Ok(query)
+    }
+
+    fn term(&self, term: &str) -> Result<Option<SearchTerm>, regex::Error> {
+        if term.is_empty() {
+            return Ok(None);
+        }
+
+        let pattern = match self.mode {
+            SearchMode::Plain => regex::escape(term),
+            SearchMode::Regex => term.to_string(),
+            SearchMode::Fuzzy => term
+                .chars()
+                .map(|c| regex::escape(c.encode_utf8(&mut [0; 4])))
+                .collect::<Vec<_>>()
+                .join(".*?"),
+        };
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .size_limit(REGEX_SIZE_LIMIT)
+            .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+            .build()?;
+
+        Ok(Some(SearchTerm {
+            term: term.to_string(),
+            regex,
+        }))
+    }
+
+    /// Returns true if the query doesn't filter anything.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.text.is_none() && self.target.is_none() && self.message.is_none()
+    }
+
+    /// Returns true if the record matches all terms of the query.
+    /// `line` is the formatted line of the record.
+    pub(crate) fn matches(&self, record: &Record, line: &str) -> bool {
+        [
+            (&self.text, line),
+            (&self.target, record.target.as_str()),
+            (&self.message, &*record.message),
+        ]
+        .into_iter()
+        .all(|(term, string)| term.as_ref().is_none_or(|term| term.regex.is_match(string)))
+    }
+
+    /// Returns true if everything matching this query also matches the previous one,
+    /// e.g. because more characters were typed.
+    /// The previous results can then be narrowed down instead of searching all records again.
+    ///
+    /// This also holds for fuzzy search, as a term containing the previous one
+    /// contains its characters in order as well.
+    pub(crate) fn narrows(&self, previous: &SearchQuery) -> bool {
+        if self.mode == SearchMode::Regex
+            || self.mode != previous.mode
+            || self.case_sensitive != previous.case_sensitive
+            || self.target.is_some()
+            || self.message.is_some()
+            || previous.target.is_some()
+            || previous.message.is_some()
+        {
+            return false;
+        }
+
+        match (&self.text, &previous.text) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(text), Some(previous_text)) if self.case_sensitive => {
+                text.term.contains(&previous_text.term)
+            }
+            (Some(text), Some(previous_text)) => text
+                .term
+                .to_lowercase()
+                .contains(&previous_text.term.to_lowercase()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Logger;
+
+    fn records() -> Logger {
+        let mut logger = Logger::default();
+        for message in [
+            "connection failed",
+            "Connected to the database",
+            "reconnecting in 5s",
+            "config loaded",
+            "CONNECTION RESET",
+            "cancel: no free connections",
+        ] {
+            logger.push(log::Level::Info, "search_test", message.to_string(), None);
+        }
+        logger
+    }
+
+    /// Returns the seqs of the records matching the query, searching all of them.
+    fn search(logger: &Logger, query: &SearchQuery) -> Vec<u64> {
+        logger
+            .logs
+            .iter()
+            .filter(|record| query.matches(record, &record.message))
+            .map(|record| record.seq)
+            .collect()
+    }
+
+    /// Types the terms one after another, narrowing the previous results whenever possible,
+    /// and checks the results are the same as searching all records.
+    fn assert_narrowing(terms: &[&str], case_sensitive: bool, mode: SearchMode) {
+        let logger = records();
+        let mut previous = SearchQuery::new("", case_sensitive, mode).unwrap();
+        let mut visible = search(&logger, &previous);
+        for term in terms {
+            let query = SearchQuery::new(term, case_sensitive, mode).unwrap();
+            assert!(query.narrows(&previous), "{term:?} should narrow");
+            visible.retain(|&seq| {
+                let record = logger.record(seq).unwrap();
+                query.matches(record, &record.message)
+            });
+            assert_eq!(visible, search(&logger, &query), "{term:?}");
+            previous = query;
+        }
+    }
+
+    #[test]
+    fn narrowing_a_plain_search_matches_a_full_search() {
+        assert_narrowing(
+            &["c", "co", "con", "conn", "connect"],
+            true,
+            SearchMode::Plain,
+        );
+    }
+
+    #[test]
+    fn narrowing_a_case_insensitive_search_matches_a_full_search() {
+        assert_narrowing(&["C", "Co", "coN", "CONNECT"], false, SearchMode::Plain);
+    }
+
+    #[test]
+    fn narrowing_a_fuzzy_search_matches_a_full_search() {
+        assert_narrowing(&["c", "cn", "cnf", "cnfa"], false, SearchMode::Fuzzy);
+    }
+
+    #[test]
+    fn regex_and_qualified_searches_never_narrow() {
+        let previous = SearchQuery::new("con", false, SearchMode::Regex).unwrap();
+        let query = SearchQuery::new("conn", false, SearchMode::Regex).unwrap();
+        assert!(!query.narrows(&previous));
+
+        let previous = SearchQuery::new("con", false, SearchMode::Plain).unwrap();
+        let query = SearchQuery::new("conn target:search", false, SearchMode::Plain).unwrap();
+        assert!(!query.narrows(&previous));
+        let query = SearchQuery::new("message:conn", false, SearchMode::Plain).unwrap();
+        assert!(!query.narrows(&previous));
+        let previous = SearchQuery::new("target:search", false, SearchMode::Plain).unwrap();
+        let query = SearchQuery::new("target:search conn", false, SearchMode::Plain).unwrap();
+        assert!(!query.narrows(&previous));
+    }
+}
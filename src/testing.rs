@@ -0,0 +1,76 @@
+//! Helpers to check what your code logs in tests.
+
+use std::sync::Mutex;
+
+use crate::{lock_logger, LogEntry};
+
+/// The time set by [`freeze_now()`].
+static FROZEN_NOW: Mutex<Option<chrono::DateTime<chrono::Local>>> = Mutex::new(None);
+
+/// Runs `f` and returns the records it logged, oldest first.
+///
+/// Installs the logger with max level `Trace` if no logger is set yet.
+/// If another logger is already set, the records are taken from the global buffer,
+/// which only contains something if the egui logger is part of it, e.g. through `multi_log`.
+///
+/// Tests run in parallel, so records logged by other threads while `f` runs are included as well.
+/// Filter them by target, or run the tests with `--test-threads=1`.
+///
+/// ```rust
+/// let records = egui_logger::testing::capture(|| {
+///     log::warn!("low on memory");
+/// });
+///
+/// assert!(records
+///     .iter()
+///     .any(|record| record.level == log::Level::Warn && record.message == "low on memory"));
+/// ```
+pub fn capture(f: impl FnOnce()) -> Vec<LogEntry> {
+    // fails if another logger is already set, the global buffer is used either way
+    if crate::installed_logger().is_none() {
+        let _ = crate::builder().max_level(log::LevelFilter::Trace).init();
+    }
+
+    let start = lock_logger().next_seq;
+    f();
+
+    let logger = lock_logger();
+    let first = logger.logs.partition_point(|record| record.seq < start);
+    logger.logs[first..].iter().map(LogEntry::from).collect()
+}
+
+/// Makes the logger use `time` as the current time until [`unfreeze_now()`] is called,
+/// so the ui renders the same way every time, e.g. for screenshot tests.
+///
+/// This applies to the timestamps of new records, the start time after
+/// [`reset_start_time()`](crate::reset_start_time), the "Time Ago" format,
+/// [fading](crate::LoggerUi::fade_old) and the [rate sparkline](crate::LoggerUi::show_rate_sparkline).
+/// The local time formats still depend on the time zone of the machine,
+/// use [`TimeFormat::Utc`](crate::TimeFormat::Utc) or [`TimeFormat::SinceStart`](crate::TimeFormat::SinceStart)
+/// to be independent of it.
+///
+/// ```rust
+/// let time = chrono::DateTime::parse_from_rfc3339("2024-05-01T12:30:05Z")
+///     .unwrap()
+///     .with_timezone(&chrono::Local);
+/// egui_logger::testing::freeze_now(time);
+/// ```
+pub fn freeze_now(time: chrono::DateTime<chrono::Local>) {
+    *FROZEN_NOW
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(time);
+}
+
+/// Makes the logger use the real current time again, see [`freeze_now()`].
+pub fn unfreeze_now() {
+    *FROZEN_NOW
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Returns the time set by [`freeze_now()`], if any.
+pub(crate) fn frozen_now() -> Option<chrono::DateTime<chrono::Local>> {
+    *FROZEN_NOW
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,92 @@
+//! Support for the [`tracing`](https://crates.io/crates/tracing) ecosystem.
+
+use std::fmt::Write;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::lock_logger;
+
+/// A [`Layer`] that stores `tracing` events in the same buffer as the [`log`] records,
+/// so they are shown in the logger ui.
+///
+/// The names of the spans the event happened in are added to its target, e.g. `my_app request:send`.
+///
+/// ```rust
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let subscriber = tracing_subscriber::registry().with(egui_logger::EguiLoggerLayer);
+/// tracing::subscriber::set_global_default(subscriber).expect("Error setting the subscriber");
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EguiLoggerLayer;
+
+impl<S> Layer<S> for EguiLoggerLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if !cfg!(feature = "capture") {
+            return;
+        }
+
+        let metadata = event.metadata();
+        let level = match *metadata.level() {
+            tracing_core::Level::ERROR => log::Level::Error,
+            tracing_core::Level::WARN => log::Level::Warn,
+            tracing_core::Level::INFO => log::Level::Info,
+            tracing_core::Level::DEBUG => log::Level::Debug,
+            tracing_core::Level::TRACE => log::Level::Trace,
+        };
+
+        let mut target = metadata.target().to_string();
+        if let Some(scope) = ctx.event_scope(event) {
+            let spans: Vec<_> = scope.from_root().map(|span| span.name()).collect();
+            target.push(' ');
+            target.push_str(&spans.join(":"));
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let message = if visitor.message.is_empty() {
+            visitor.fields.trim_start().to_string()
+        } else {
+            visitor.message + &visitor.fields
+        };
+
+        let location = metadata
+            .file()
+            .zip(metadata.line())
+            .map(|(file, line)| format!("{file}:{line}"));
+
+        lock_logger().push(level, &target, message, location);
+    }
+}
+
+/// Collects the `message` of an event, followed by its other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
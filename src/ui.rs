@@ -1,43 +1,132 @@
-use std::sync::Mutex;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
 
 use egui::{text::LayoutJob, Align, Color32, FontSelection, RichText, Style};
-use regex::{Regex, RegexBuilder};
+use hashbrown::{HashMap, HashSet};
 
-use crate::{Logger, Record, LEVELS, LOGGER};
+use crate::format::{
+    self, FormatOptions, LevelAlign, Padding, TimeFormat, TimePosition, TimePrecision,
+};
+use crate::search::{SearchMode, SearchQuery};
+use crate::{
+    all_levels, level_index, lock_logger, LogEntry, LogStore, Logger, Record, LEVELS, LEVEL_COUNT,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum TimePrecision {
-    Seconds,
-    Milliseconds,
+enum CopyFormat {
+    Full,
+    MessageOnly,
+    Csv,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum TimeFormat {
-    Utc,
-    LocalTime,
-    SinceStart,
-}
+/// The opacity of the oldest records with [`LoggerUi::fade_old()`].
+const MIN_FADE_OPACITY: f32 = 0.3;
+
+/// The number of seconds shown by the [rate sparkline](LoggerUi::show_rate_sparkline).
+const SPARKLINE_SECONDS: usize = 60;
+const SPARKLINE_HEIGHT: f32 = 24.0;
+
+/// An action of the [context menu](LoggerUi::context_menu_extra), run after the buffer is unlocked.
+type MenuAction = Box<dyn FnOnce()>;
+type ContextMenuExtra = Arc<dyn Fn(&mut egui::Ui, &LogEntry) -> Option<MenuAction> + Send + Sync>;
+type FilterPredicate = Arc<dyn Fn(&LogEntry) -> bool + Send + Sync>;
 
+#[derive(Clone)]
 struct LoggerStyle {
     enable_regex: bool,
     enable_ctx_menu: bool,
+    enable_shortcuts: bool,
+    enable_clear_button: bool,
+    enable_colors_button: bool,
+    enable_toolbar: bool,
+    enable_footer: bool,
+    show_hidden_counts: bool,
+    confirm_clear: bool,
     show_target: bool,
-    time_precision: TimePrecision,
-    time_format: TimeFormat,
+    level_chips: bool,
+    level_badge: bool,
+    show_rate_sparkline: bool,
+    level_dropdown: bool,
+    linkify: bool,
+    max_target_width: Option<usize>,
+    copy_with_ansi: bool,
+    horizontal_scroll: bool,
+    row_background: bool,
+    fill_height: bool,
+    group_by_category: bool,
+    max_height: Option<f32>,
+    show_date_separators: bool,
+    display_limit: Option<usize>,
+    column_delimiter: Option<char>,
+    truncate_message: Option<usize>,
+    fade_old: Option<std::time::Duration>,
+    stable_columns: bool,
+    time_tooltip: bool,
+    /// How the records are formatted as text.
+    format: FormatOptions,
+    context_menu_extra: Option<ContextMenuExtra>,
+    filter: Option<FilterPredicate>,
+    filter_replaces_builtin: bool,
 
+    auto_colors: bool,
+    /// The colors that were set explicitly and aren't derived from the theme.
+    custom_colors: CustomColors,
     warn_color: Color32,
     error_color: Color32,
     highlight_color: Color32,
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+struct CustomColors {
+    warn: bool,
+    error: bool,
+    highlight: bool,
+}
+
 impl Default for LoggerStyle {
     fn default() -> Self {
         Self {
             show_target: true,
+            level_chips: false,
+            level_badge: false,
+            show_rate_sparkline: false,
+            level_dropdown: false,
+            linkify: false,
+            max_target_width: None,
             enable_regex: true,
             enable_ctx_menu: true,
-            time_format: TimeFormat::LocalTime,
-            time_precision: TimePrecision::Seconds,
+            enable_shortcuts: false,
+            enable_clear_button: true,
+            enable_colors_button: false,
+            enable_toolbar: true,
+            enable_footer: true,
+            show_hidden_counts: false,
+            confirm_clear: false,
+            copy_with_ansi: false,
+            horizontal_scroll: false,
+            row_background: false,
+            fill_height: false,
+            group_by_category: false,
+            max_height: None,
+            show_date_separators: false,
+            display_limit: None,
+            column_delimiter: None,
+            truncate_message: None,
+            fade_old: None,
+            stable_columns: false,
+            time_tooltip: true,
+            format: FormatOptions {
+                time_format: TimeFormat::default(),
+                time_precision: TimePrecision::default(),
+                escape_control_chars: true,
+                ..FormatOptions::default()
+            },
+            context_menu_extra: None,
+            filter: None,
+            filter_replaces_builtin: false,
+            auto_colors: true,
+            custom_colors: CustomColors::default(),
             warn_color: Color32::YELLOW,
             error_color: Color32::RED,
             highlight_color: Color32::LIGHT_GRAY,
@@ -45,15 +134,131 @@ impl Default for LoggerStyle {
     }
 }
 
+/// An interaction with a single row of the log.
+enum RowEvent {
+    Clicked,
+    ToggleExpanded,
+    TogglePinned,
+    Copy(String),
+    MenuAction(MenuAction),
+}
+
+/// What has to wait until the buffer is unlocked, because it runs user code that may log.
+#[derive(Default)]
+struct Deferred {
+    /// The text that was copied.
+    copied: Option<String>,
+    /// The action of the context menu that was clicked.
+    menu_action: Option<MenuAction>,
+}
+
+/// The filter that hid a record, checked in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HiddenBy {
+    Filter,
+    Category,
+    Level,
+    Search,
+}
+
+/// The records that passed the filters, so they don't have to be filtered every frame.
+#[derive(Default)]
+struct FilterCache {
+    /// The sequence numbers of the visible records, oldest first.
+    visible: Vec<u64>,
+    /// The sequence numbers of the hidden records and why they're hidden, oldest first.
+    hidden: Vec<(u64, HiddenBy)>,
+    /// Records with a lower sequence number have already been filtered.
+    next_seq: u64,
+    /// The categories generation of the log when the cache was built.
+    categories_generation: u64,
+    valid: bool,
+    /// The filters only got stricter, so the visible records just have to be filtered again.
+    narrow: bool,
+}
+
+/// The results of the [filter predicate](LoggerUi::filter), so it only runs once for every record.
+#[derive(Default)]
+struct FilterResults {
+    /// Whether the predicate accepted each record, by seq.
+    accepted: HashMap<u64, bool>,
+    /// Records with a lower sequence number have already been passed to the predicate.
+    next_seq: u64,
+}
+
+/// The heights of the rows when they were last drawn, so only the rows in view have to be laid out.
+#[derive(Default)]
+struct RowHeights {
+    /// The width the rows were drawn at, they wrap differently at other widths.
+    width: f32,
+    /// The height of each row including the spacing after it, by seq.
+    heights: HashMap<u64, f32>,
+}
+
+/// An error returned by [`LoggerUi::try_show()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoggerError {
+    /// The ui state is poisoned, because a thread panicked while drawing it.
+    /// [`LoggerUi::reset()`] recovers from it.
+    UiPoisoned,
+}
+
+impl std::fmt::Display for LoggerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UiPoisoned => write!(f, "the logger ui state is poisoned"),
+        }
+    }
+}
+
+impl std::error::Error for LoggerError {}
+
 /// The Ui for the Logger.
 /// You can use [`logger_ui()`] to get a default instance of the LoggerUi
 pub struct LoggerUi {
-    loglevels: [bool; log::Level::Trace as usize],
+    loglevels: [bool; LEVEL_COUNT],
+    /// The levels to restore when the "Errors only" filter is turned off again.
+    errors_only_restore: Option<[bool; LEVEL_COUNT]>,
     search_term: String,
-    regex: Option<Regex>,
+    query: SearchQuery,
+    /// The error of the current search term, the last valid query is kept meanwhile.
+    search_error: Option<regex::Error>,
+    /// The search was set by a builder and the query has to be compiled on the next render.
+    search_changed: bool,
+    /// The wildcard pattern in the Categories menu.
+    category_pattern: String,
+    filter_cache: FilterCache,
+    filter_results: FilterResults,
+    row_heights: RowHeights,
+    /// The anchor and cursor seq of the selected range of records.
+    selection: Option<(u64, u64)>,
+    /// The seqs of the records whose truncated message is shown in full.
+    expanded: HashSet<u64>,
+    /// The seqs of the records pinned above the log.
+    pinned: BTreeSet<u64>,
+    /// How many more records than the display limit are shown, increased by "Show more".
+    display_more: usize,
+    /// The width of the first column of the displayed messages, see [`LoggerUi::align_columns()`].
+    column_width: usize,
+    /// The largest column widths seen so far, see [`LoggerUi::stable_columns()`].
+    stable_padding: Padding,
+    id: egui::Id,
+    /// The buffer that is shown, the global one if None.
+    store: Option<LogStore>,
     search_case_sensitive: bool,
-    search_use_regex: bool,
+    search_mode: SearchMode,
     max_log_length: usize,
+    follow: bool,
+    /// The frame the log was last shown in, to notice when it is shown again.
+    last_pass_nr: Option<u64>,
+    /// The height of the footer in the last frame, reserved below the log.
+    footer_height: Option<f32>,
+    on_new_error: Option<Box<dyn FnMut() + Send>>,
+    on_copy: Option<Box<dyn FnMut(String) + Send>>,
+    /// The error count of the log when the ui was last drawn.
+    last_error_count: usize,
+    /// Whether new errors were logged when the ui was last drawn.
+    new_errors_shown: bool,
     style: LoggerStyle,
 }
 
@@ -61,17 +266,117 @@ impl Default for LoggerUi {
     fn default() -> Self {
         Self {
             loglevels: [true, true, true, false, false],
+            errors_only_restore: None,
             search_term: String::new(),
             search_case_sensitive: false,
-            regex: None,
-            search_use_regex: false,
+            query: SearchQuery::default(),
+            search_error: None,
+            search_changed: false,
+            category_pattern: String::new(),
+            filter_cache: FilterCache::default(),
+            filter_results: FilterResults::default(),
+            row_heights: RowHeights::default(),
+            selection: None,
+            expanded: HashSet::new(),
+            pinned: BTreeSet::new(),
+            display_more: 0,
+            column_width: 0,
+            stable_padding: Padding::default(),
+            id: egui::Id::new("egui_logger"),
+            store: None,
+            search_mode: SearchMode::default(),
             max_log_length: 1000,
+            follow: true,
+            last_pass_nr: None,
+            footer_height: None,
+            on_new_error: None,
+            on_copy: None,
+            last_error_count: 0,
+            new_errors_shown: false,
             style: LoggerStyle::default(),
         }
     }
 }
 
+/// The state of the logger uis by their id, see [`LoggerUi::id()`].
+static LOGGER_UIS: LazyLock<Mutex<HashMap<egui::Id, Arc<Mutex<LoggerUi>>>>> =
+    LazyLock::new(Default::default);
+
+/// Locks the ui states, the map stays consistent even if a thread panicked while holding it.
+fn lock_logger_uis() -> MutexGuard<'static, HashMap<egui::Id, Arc<Mutex<LoggerUi>>>> {
+    LOGGER_UIS.lock().unwrap_or_else(|poisoned| {
+        LOGGER_UIS.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Locks the buffer shown by a logger ui, the global one if it has no store.
+fn lock_store(store: Option<&LogStore>) -> MutexGuard<'_, Logger> {
+    match store {
+        Some(store) => store.lock(),
+        None => lock_logger(),
+    }
+}
+
+/// A reusable configuration for the [`LoggerUi`].
+/// You can get one by configuring a [`LoggerUi`] once and calling [`LoggerUi::config()`],
+/// then store it in your app state and use [`LoggerUi::from_config()`] each frame.
+///
+/// ```rust
+/// let config = egui_logger::logger_ui()
+///     .enable_regex(false)
+///     .show_target(false)
+///     .config();
+///
+/// # let ctx = egui::Context::default();
+/// # let _ = ctx.run(Default::default(), |ctx| {
+/// egui::Window::new("Log").show(ctx, |ui| {
+///     egui_logger::LoggerUi::from_config(config.clone()).show(ui);
+/// });
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct LoggerUiConfig {
+    loglevels: [bool; LEVEL_COUNT],
+    max_log_length: usize,
+    follow: bool,
+    style: LoggerStyle,
+}
+
+impl Default for LoggerUiConfig {
+    fn default() -> Self {
+        LoggerUi::default().config()
+    }
+}
+
 impl LoggerUi {
+    /// Creates a LoggerUi from a previously created [`LoggerUiConfig`]
+    pub fn from_config(config: LoggerUiConfig) -> Self {
+        Self {
+            loglevels: config.loglevels,
+            max_log_length: config.max_log_length,
+            follow: config.follow,
+            style: config.style,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the configuration of this LoggerUi, so it can be reused
+    pub fn config(&self) -> LoggerUiConfig {
+        LoggerUiConfig {
+            loglevels: self.loglevels,
+            max_log_length: self.max_log_length,
+            follow: self.follow,
+            style: self.style.clone(),
+        }
+    }
+
+    /// Returns the options the records are formatted with when they're copied,
+    /// so [`format_entry()`](crate::format_entry()) formats them the same way
+    pub fn format_options(&self) -> FormatOptions {
+        self.style.format.clone()
+    }
+
     /// Enable or disable the regex search
     /// Default is true
     #[inline] // i think the compiler already does this
@@ -88,324 +393,2159 @@ impl LoggerUi {
         self
     }
 
-    /// Enable or disable showing the [target](log::Record::target())
+    /// Enable or disable the Clear button, this also disables the clear shortcut
     /// Default is true
     #[inline]
-    pub fn show_target(mut self, enable: bool) -> Self {
-        self.style.show_target = enable;
+    pub fn enable_clear_button(mut self, enable: bool) -> Self {
+        self.style.enable_clear_button = enable;
         self
     }
 
+    /// Enable or disable asking for confirmation before the Clear button clears the log
+    /// Default is false
     #[inline]
-    pub fn warn_color(mut self, color: Color32) -> Self {
-        self.style.warn_color = color;
+    pub fn confirm_clear(mut self, enable: bool) -> Self {
+        self.style.confirm_clear = enable;
         self
     }
 
+    /// Enable or disable keyboard shortcuts:
+    /// - `Ctrl + F` focuses the search box
+    /// - `Ctrl + L` clears the log, without confirmation
+    /// - `Esc` clears the search term while the search box is focused
+    ///
+    /// `Cmd` is used instead of `Ctrl` on macOS.
+    /// Default is false
     #[inline]
-    pub fn error_color(mut self, color: Color32) -> Self {
-        self.style.error_color = color;
+    pub fn enable_shortcuts(mut self, enable: bool) -> Self {
+        self.style.enable_shortcuts = enable;
         self
     }
 
+    /// Enable or disable the Colors menu to change the [error](Self::error_color),
+    /// [warning](Self::warn_color) and [highlight](Self::highlight_color) colors at runtime
+    /// Default is false
     #[inline]
-    pub fn highlight_color(mut self, color: Color32) -> Self {
-        self.style.highlight_color = color;
+    pub fn enable_colors_button(mut self, enable: bool) -> Self {
+        self.style.enable_colors_button = enable;
         self
     }
 
-    pub(crate) fn log_ui(self) -> &'static Mutex<LoggerUi> {
-        static LOGGER_UI: std::sync::OnceLock<Mutex<LoggerUi>> = std::sync::OnceLock::new();
-        LOGGER_UI.get_or_init(|| self.into())
+    /// Enable or disable the rows above the log with the buttons, menus and search.
+    /// Disable it together with the [footer](Self::enable_footer) to only show the log.
+    /// Default is true
+    #[inline]
+    pub fn enable_toolbar(mut self, enable: bool) -> Self {
+        self.style.enable_toolbar = enable;
+        self
     }
 
-    /// This draws the Logger UI
-    pub fn show(self, ui: &mut egui::Ui) {
-        if let Ok(ref mut logger_ui) = self.log_ui().lock() {
-            logger_ui.ui(ui);
-        } else {
-            ui.colored_label(Color32::RED, "Something went wrong loading the log");
-        }
+    /// Enable or disable the row below the log with the record counts and the Copy menu
+    /// Default is true
+    #[inline]
+    pub fn enable_footer(mut self, enable: bool) -> Self {
+        self.style.enable_footer = enable;
+        self
     }
 
-    pub(crate) fn ui(&mut self, ui: &mut egui::Ui) {
-        let Ok(ref mut logger) = LOGGER.lock() else {
-            return;
-        };
+    /// Enable or disable showing how many records each filter hides in the footer,
+    /// e.g. `Hidden: 12 by level, 30 by search`
+    /// Default is false
+    #[inline]
+    pub fn show_hidden_counts(mut self, enable: bool) -> Self {
+        self.style.show_hidden_counts = enable;
+        self
+    }
 
-        {
-            let dropped_entries = logger.logs.len().saturating_sub(self.max_log_length);
-            drop(logger.logs.drain(..dropped_entries));
-        }
+    /// Enable or disable showing the [target](log::Record::target())
+    /// Default is true
+    #[inline]
+    pub fn show_target(mut self, enable: bool) -> Self {
+        self.style.show_target = enable;
+        self
+    }
 
-        ui.horizontal(|ui| {
-            if ui.button("Clear").clicked() {
-                logger.logs.clear();
-            }
-            ui.menu_button("Log Levels", |ui| {
-                for level in LEVELS {
-                    if ui
-                        .selectable_label(self.loglevels[level as usize - 1], level.as_str())
-                        .clicked()
-                    {
-                        self.loglevels[level as usize - 1] = !self.loglevels[level as usize - 1];
-                    }
-                }
-            });
+    /// Enable or disable showing a toggle for each level in the toolbar, e.g. `E W I D T`,
+    /// instead of the Log Levels menu
+    /// Default is false
+    #[inline]
+    pub fn level_chips(mut self, enable: bool) -> Self {
+        self.style.level_chips = enable;
+        self
+    }
 
-            ui.menu_button("Categories", |ui| {
-                if ui.button("Select All").clicked() {
-                    for (_, enabled) in logger.categories.iter_mut() {
-                        *enabled = true;
-                    }
-                }
+    /// Enable or disable a single dropdown in the toolbar that shows all levels at or above the selected one,
+    /// instead of the Log Levels menu
+    /// Default is false
+    #[inline]
+    pub fn level_dropdown(mut self, enable: bool) -> Self {
+        self.style.level_dropdown = enable;
+        self
+    }
 
-                if ui.button("Unselect All").clicked() {
-                    for (_, enabled) in logger.categories.iter_mut() {
-                        *enabled = false;
-                    }
-                }
+    /// Enable or disable showing the level as a colored dot instead of its name, e.g. `● `.
+    /// Copied text still contains the level name
+    /// Default is false
+    #[inline]
+    pub fn level_badge(mut self, enable: bool) -> Self {
+        self.style.level_badge = enable;
+        self
+    }
 
-                for (category, enabled) in logger.categories.iter_mut() {
-                    if ui.selectable_label(*enabled, category).clicked() {
-                        *enabled = !*enabled;
-                    }
-                }
-            });
+    /// Enable or disable a small chart above the log with the number of records per second
+    /// over the last minute, to spot bursts
+    /// Default is false
+    #[inline]
+    pub fn show_rate_sparkline(mut self, enable: bool) -> Self {
+        self.style.show_rate_sparkline = enable;
+        self
+    }
 
-            ui.menu_button("Time", |ui| {
-                ui.radio_value(&mut self.style.time_format, TimeFormat::Utc, "UTC");
-                ui.radio_value(
-                    &mut self.style.time_format,
-                    TimeFormat::LocalTime,
-                    "Local Time",
-                );
-                ui.radio_value(
-                    &mut self.style.time_format,
-                    TimeFormat::SinceStart,
-                    "Since Start",
-                );
+    /// Enable or disable the brackets around the level, e.g. `[WARN ]`
+    /// Default is true
+    #[inline]
+    pub fn level_brackets(mut self, enable: bool) -> Self {
+        self.style.format.level_brackets = enable;
+        self
+    }
 
-                ui.separator();
+    /// Sets the alignment of the level within its column, e.g. `[ WARN]` with [`Align::Max`]
+    /// Default is [`Align::Min`]
+    #[inline]
+    pub fn level_align(mut self, align: Align) -> Self {
+        self.style.format.level_align = match align {
+            Align::Min => LevelAlign::Left,
+            Align::Center => LevelAlign::Center,
+            Align::Max => LevelAlign::Right,
+        };
+        self
+    }
 
-                ui.radio_value(
-                    &mut self.style.time_precision,
-                    TimePrecision::Seconds,
-                    "Seconds",
-                );
-                ui.radio_value(
-                    &mut self.style.time_precision,
-                    TimePrecision::Milliseconds,
-                    "Milliseconds",
-                );
-            });
-        });
+    /// Sets the text between the target and the message, e.g. `" | "`
+    /// Default is `": "`
+    #[inline]
+    pub fn target_separator(mut self, separator: impl Into<String>) -> Self {
+        self.style.format.target_separator = separator.into();
+        self
+    }
 
-        ui.horizontal(|ui| {
-            ui.label("Search: ");
-            let response = ui.text_edit_singleline(&mut self.search_term);
+    /// Sets the levels that are shown initially to all levels up to `max_level`,
+    /// they can still be changed in the Log Levels menu
+    /// Default is [`Info`](log::LevelFilter::Info)
+    #[inline]
+    pub fn log_levels(mut self, max_level: log::LevelFilter) -> Self {
+        self.loglevels = LEVELS.map(|level| level <= max_level);
+        self.errors_only_restore = None;
+        self
+    }
 
-            let mut config_changed = false;
+    /// Sets the levels that are shown initially to `level` and all more severe ones,
+    /// e.g. `Warn` shows warnings and errors
+    /// Default is [`Info`](log::Level::Info)
+    #[inline]
+    pub fn min_level(self, level: log::Level) -> Self {
+        self.log_levels(level.to_level_filter())
+    }
 
-            if ui
-                .selectable_label(self.search_case_sensitive, "Aa")
-                .on_hover_text("Case sensitive")
-                .clicked()
-            {
-                self.search_case_sensitive = !self.search_case_sensitive;
-                config_changed = true;
-            }
+    /// Shows all levels up to the global max level initially,
+    /// e.g. the one set by [`Builder::init()`](crate::Builder::init),
+    /// so everything that is logged is visible.
+    #[inline]
+    pub fn default_levels_from_max(self) -> Self {
+        self.log_levels(log::max_level())
+    }
 
-            if self.style.enable_regex
-                && ui
-                    .selectable_label(self.search_use_regex, ".*")
-                    .on_hover_text("Use regex")
-                    .clicked()
-            {
-                self.search_use_regex = !self.search_use_regex;
-                config_changed = true;
-            }
+    /// Enable or disable automatically scrolling to new log records
+    /// Default is true
+    #[inline]
+    pub fn follow(mut self, enable: bool) -> Self {
+        self.follow = enable;
+        self
+    }
 
-            if self.style.enable_regex
-                && self.search_use_regex
-                && (response.changed() || config_changed)
-            {
-                self.regex = RegexBuilder::new(&self.search_term)
-                    .case_insensitive(!self.search_case_sensitive)
-                    .build()
-                    .ok()
-            }
-        });
+    /// Enable or disable showing the sequence number of each record, e.g. `#10423`
+    /// Default is false
+    #[inline]
+    pub fn include_seq(mut self, enable: bool) -> Self {
+        self.style.format.include_seq = enable;
+        self
+    }
 
-        ui.horizontal(|ui| {
-            ui.label("Max Log output");
-            ui.add(egui::widgets::DragValue::new(&mut self.max_log_length).speed(1));
-        });
+    /// Enable or disable horizontal scrolling for lines wider than the window.
+    /// Default is false
+    #[inline]
+    pub fn horizontal_scroll(mut self, enable: bool) -> Self {
+        self.style.horizontal_scroll = enable;
+        self
+    }
 
-        ui.separator();
+    /// Enable or disable a tinted background behind error and warning rows
+    /// Default is false
+    #[inline]
+    pub fn row_background(mut self, enable: bool) -> Self {
+        self.style.row_background = enable;
+        self
+    }
 
-        let mut logs_displayed: usize = 0;
+    /// Enable or disable filling the available height, even if there are only a few records.
+    /// Useful when the logger is shown in a panel instead of a window
+    /// Default is false
+    #[inline]
+    pub fn fill_height(mut self, enable: bool) -> Self {
+        self.style.fill_height = enable;
+        self
+    }
 
-        let time_padding = logger.logs.last().map_or(0, |record| {
-            format_time(record.time, &self.style, logger.start_time).len()
-        });
+    /// Enable or disable grouping the records into collapsible sections by category
+    /// Default is false
+    #[inline]
+    pub fn group_by_category(mut self, enable: bool) -> Self {
+        self.style.group_by_category = enable;
+        self
+    }
 
-        egui::ScrollArea::vertical()
-            .auto_shrink([false, true])
-            .max_height(ui.available_height() - 30.0)
-            .stick_to_bottom(true)
-            .show(ui, |ui| {
-                logger.logs.iter().for_each(|record| {
-                    // Filter out categories that are disabled
-                    if let Some(&false) = logger.categories.get(&record.target) {
-                        return;
-                    }
+    /// Sets the maximum height of the log list.
+    /// `None` uses all the available height, minus the footer
+    /// Default is None
+    #[inline]
+    pub fn max_height(mut self, max_height: Option<f32>) -> Self {
+        self.style.max_height = max_height;
+        self
+    }
 
-                    let layout_job = format_record(logger, &self.style, record, time_padding);
+    /// Enable or disable a divider showing the date whenever it changes between two records
+    /// Default is false
+    #[inline]
+    pub fn show_date_separators(mut self, enable: bool) -> Self {
+        self.style.show_date_separators = enable;
+        self
+    }
 
-                    let raw_text = layout_job.text.clone();
+    /// Sets how many of the newest matching records are drawn,
+    /// older ones can be shown with the "Show more" button.
+    /// Unlike the "Max Log output" this doesn't remove records,
+    /// it only limits the cost of drawing them.
+    /// Default is no limit
+    #[inline]
+    pub fn display_limit(mut self, limit: usize) -> Self {
+        self.style.display_limit = Some(limit);
+        self
+    }
 
-                    // Filter out log levels that are disabled via regex or log level
-                    if (!self.search_term.is_empty() && !self.match_string(&raw_text))
-                        || !(self.loglevels[record.level as usize - 1])
-                    {
-                        return;
-                    }
+    /// Enable or disable showing control characters in messages escaped, e.g. `\r` or `\u{1b}`,
+    /// so they can't mess up the layout. Line breaks are kept.
+    /// This also applies to copied text, the context menu of a record can still copy the raw message.
+    /// Default is true
+    #[inline]
+    pub fn escape_control_chars(mut self, enable: bool) -> Self {
+        self.style.format.escape_control_chars = enable;
+        self
+    }
 
-                    let response = ui.label(layout_job);
+    /// Sets a delimiter, e.g. `':'` or `'\t'`, to split messages into two columns at its first occurrence.
+    /// The part before it is padded to the widest one of the displayed records,
+    /// so the rest of the messages line up.
+    /// Default is None
+    #[inline]
+    pub fn align_columns(mut self, delimiter: Option<char>) -> Self {
+        self.style.column_delimiter = delimiter;
+        self
+    }
 
-                    if self.style.enable_ctx_menu {
-                        response.clone().context_menu(|ui| {
-                            if self.style.show_target {
-                                ui.label(&record.target);
-                            }
-                            response.highlight();
-                            let string_format = format!("[{}]: {}", record.level, record.message);
+    /// Sets the age at which records are drawn faintest, so recent records stand out.
+    /// Records fade in proportion to their age up to then, but stay readable.
+    /// Default is None
+    #[inline]
+    pub fn fade_old(mut self, duration: Option<std::time::Duration>) -> Self {
+        self.style.fade_old = duration;
+        self
+    }
 
-                            // the vertical layout is because otherwise text spacing gets weird
-                            ui.vertical(|ui| {
-                                ui.monospace(string_format);
-                            });
+    /// Enable or disable showing `http://` and `https://` URLs in messages as clickable links
+    /// Default is false
+    #[inline]
+    pub fn linkify(mut self, enable: bool) -> Self {
+        self.style.linkify = enable;
+        self
+    }
 
-                            if ui.button("Copy").clicked() {
-                                ui.ctx().copy_text(raw_text);
-                            }
-                        });
-                    }
+    /// Enable or disable keeping the time and target columns at the widest width seen so far,
+    /// so the log doesn't shift around when wider records arrive or old ones are removed.
+    /// Default is false
+    #[inline]
+    pub fn stable_columns(mut self, enable: bool) -> Self {
+        self.style.stable_columns = enable;
+        self
+    }
 
-                    logs_displayed += 1;
-                });
-            });
+    /// Sets the maximum number of characters of the target column,
+    /// longer targets are shortened to their end, e.g. `…vulkan::instance`.
+    /// The full target is shown when hovering the record and in its context menu.
+    /// Default is no limit
+    #[inline]
+    pub fn max_target_width(mut self, max_chars: usize) -> Self {
+        self.style.max_target_width = Some(max_chars);
+        self
+    }
 
-        ui.horizontal(|ui| {
-            ui.label(format!("Log size: {}", logger.logs.len()));
-            ui.label(format!("Displayed: {}", logs_displayed));
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if ui.button("Copy").clicked() {
-                    ui.output_mut(|o| {
-                        let mut out_string = String::new();
-                        logger
-                            .logs
-                            .iter()
-                            .take(self.max_log_length)
-                            .for_each(|record| {
-                                out_string.push_str(
-                                    &format_record(logger, &self.style, record, time_padding).text,
-                                );
-                                out_string.push_str(" \n");
-                            });
-                        o.copied_text = out_string;
-                    });
-                }
-            });
-        });
+    /// Sets the maximum number of characters of a message that are shown,
+    /// longer messages are cut off with `…`.
+    /// Double-click a record or use its context menu to show the full message,
+    /// copying always uses the full message.
+    /// Default is None
+    #[inline]
+    pub fn truncate_message(mut self, max_chars: Option<usize>) -> Self {
+        self.style.truncate_message = max_chars;
+        self
     }
 
-    fn match_string(&self, string: &str) -> bool {
-        if self.search_use_regex {
-            if let Some(matcher) = &self.regex {
-                matcher.is_match(string)
-            } else {
-                false
-            }
-        } else if self.search_case_sensitive {
-            string.contains(&self.search_term)
-        } else {
-            string
-                .to_lowercase()
-                .contains(&self.search_term.to_lowercase())
-        }
+    /// Adds items to the context menu of a record, below the built-in ones.
+    /// The callback gets the record the menu was opened for, e.g. to open its location in an editor.
+    ///
+    /// The log buffer is locked while the menu is drawn, so the callback must not log.
+    /// Instead it returns what a clicked item does, which runs after the buffer is unlocked and can log.
+    ///
+    /// ```rust
+    /// # let ctx = egui::Context::default();
+    /// # let _ = ctx.run(Default::default(), |ctx| {
+    /// # egui::CentralPanel::default().show(ctx, |ui| {
+    /// egui_logger::logger_ui()
+    ///     .context_menu_extra(|ui, entry| {
+    ///         let location = entry.location.clone()?;
+    ///         if ui.button("Open in editor").clicked() {
+    ///             return Some(Box::new(move || log::info!("opening {location}")));
+    ///         }
+    ///         None
+    ///     })
+    ///     .show(ui);
+    /// # });
+    /// # });
+    /// ```
+    #[inline]
+    pub fn context_menu_extra(
+        mut self,
+        add_contents: impl Fn(&mut egui::Ui, &LogEntry) -> Option<Box<dyn FnOnce()>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.style.context_menu_extra = Some(Arc::new(add_contents));
+        self
     }
-}
 
-/// Returns a default LoggerUi.
-/// You have to call [`LoggerUi::show()`] to display the logger
-pub fn logger_ui() -> LoggerUi {
-    LoggerUi::default()
-}
+    /// Sets a predicate that decides which records are shown, in addition to the level, category and search filters.
+    ///
+    /// The result is cached, so the predicate is called once for every record.
+    /// Don't depend on anything that changes over time like the current time.
+    /// It runs on copies of the records while the log buffer is unlocked, so it can log.
+    #[inline]
+    pub fn filter(mut self, predicate: impl Fn(&LogEntry) -> bool + Send + Sync + 'static) -> Self {
+        self.style.filter = Some(Arc::new(predicate));
+        self
+    }
 
-fn format_time(
-    time: chrono::DateTime<chrono::Local>,
-    style: &LoggerStyle,
-    start_time: chrono::DateTime<chrono::Local>,
-) -> String {
-    match (style.time_format, style.time_precision) {
-        (TimeFormat::Utc, TimePrecision::Seconds) => time
-            .to_utc()
-            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
-        (TimeFormat::Utc, TimePrecision::Milliseconds) => time
-            .to_utc()
-            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
-        (TimeFormat::LocalTime, TimePrecision::Seconds) => time.format("%T").to_string(),
-        (TimeFormat::LocalTime, TimePrecision::Milliseconds) => time.format("%T%.3f").to_string(),
-        (TimeFormat::SinceStart, TimePrecision::Seconds) => {
-            let duration = time - start_time;
-            let h = duration.num_hours() % 24;
-            let m = duration.num_minutes() % 60;
-            let s = duration.num_seconds() % 60;
-            match (h, m, s) {
-                (0, 0, s) => format!("{s}s"),
-                (0, m, s) => format!("{m}m {s}s"),
-                (h, m, s) => format!("{h}h {m}m {s}s"),
-            }
-        }
-        (TimeFormat::SinceStart, TimePrecision::Milliseconds) => {
-            let duration = time - start_time;
-            let h = duration.num_hours() % 24;
-            let m = duration.num_minutes() % 60;
-            let s = duration.num_seconds() % 60;
-            let ms = duration.num_milliseconds() % 1000;
-            match (h, m, s, ms) {
-                (0, 0, 0, ms) => format!("{ms}ms"),
-                (0, 0, s, ms) => format!("{s}s {ms}ms"),
-                (0, m, s, ms) => format!("{m}m {s}s {ms}ms"),
-                (h, m, s, ms) => format!("{h}h {m}m {s}s {ms}ms"),
-            }
-        }
+    /// Enable or disable using only the [`filter()`](Self::filter) predicate,
+    /// ignoring the level, category and search filters.
+    /// Default is false
+    #[inline]
+    pub fn filter_replaces_builtin(mut self, enable: bool) -> Self {
+        self.style.filter_replaces_builtin = enable;
+        self
     }
-}
 
-fn format_record(
+    /// Sets a callback that is called when the ui is drawn and new errors have been logged since the last time.
+    /// Use this to flash the window or play a sound
+    #[inline]
+    pub fn on_new_error(mut self, callback: impl FnMut() + Send + 'static) -> Self {
+        self.on_new_error = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets a callback that gets the copied text instead of the clipboard,
+    /// e.g. to save it to a file where clipboard access is restricted.
+    /// Default is copying to the clipboard
+    #[inline]
+    pub fn on_copy(mut self, callback: impl FnMut(String) + Send + 'static) -> Self {
+        self.on_copy = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the initial format of the timestamps, it can still be changed in the Time menu
+    /// Default is [`TimeFormat::LocalTime`]
+    #[inline]
+    pub fn time_format(mut self, format: TimeFormat) -> Self {
+        self.style.format.time_format = format;
+        self
+    }
+
+    /// Sets where the timestamp is shown, e.g. at the end of the line like compiler output
+    /// Default is [`TimePosition::Leading`]
+    #[inline]
+    pub fn time_position(mut self, position: TimePosition) -> Self {
+        self.style.format.time_position = position;
+        self
+    }
+
+    /// Enable or disable a tooltip with the full UTC timestamp of a record when hovering it,
+    /// regardless of the [time format](Self::time_format)
+    /// Default is true
+    #[inline]
+    pub fn time_tooltip(mut self, enable: bool) -> Self {
+        self.style.time_tooltip = enable;
+        self
+    }
+
+    /// Sets the initial precision of the timestamps, it can still be changed in the Time menu
+    /// Default is [`TimePrecision::Seconds`]
+    #[inline]
+    pub fn time_precision(mut self, precision: TimePrecision) -> Self {
+        self.style.format.time_precision = precision;
+        self
+    }
+
+    /// Enable or disable ANSI color codes in the text copied by the Copy button
+    /// Default is false
+    #[inline]
+    pub fn copy_with_ansi(mut self, enable: bool) -> Self {
+        self.style.copy_with_ansi = enable;
+        self
+    }
+
+    /// Sets a custom label for the log levels, e.g. icons or localized text.
+    /// The level column is padded to the longest label.
+    /// Default is the level name, e.g. `[WARN ]`
+    #[inline]
+    pub fn level_label(
+        mut self,
+        label: impl Fn(log::Level) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.style.format.level_label = Some(Arc::new(label));
+        self
+    }
+
+    #[inline]
+    pub fn warn_color(mut self, color: Color32) -> Self {
+        self.style.warn_color = color;
+        self.style.custom_colors.warn = true;
+        self
+    }
+
+    #[inline]
+    pub fn error_color(mut self, color: Color32) -> Self {
+        self.style.error_color = color;
+        self.style.custom_colors.error = true;
+        self
+    }
+
+    #[inline]
+    pub fn highlight_color(mut self, color: Color32) -> Self {
+        self.style.highlight_color = color;
+        self.style.custom_colors.highlight = true;
+        self
+    }
+
+    /// Enable or disable deriving the colors from the egui theme, so they're readable in dark and light mode.
+    /// Colors set with [`warn_color()`](Self::warn_color), [`error_color()`](Self::error_color)
+    /// or [`highlight_color()`](Self::highlight_color) are kept.
+    /// Default is true
+    #[inline]
+    pub fn auto_colors(mut self, enable: bool) -> Self {
+        self.style.auto_colors = enable;
+        self
+    }
+
+    /// Sets the initial search term, e.g. to open the logger already filtered.
+    /// Default is an empty search term
+    #[inline]
+    pub fn search_term(mut self, search_term: impl Into<String>) -> Self {
+        self.search_term = search_term.into();
+        self.search_changed = true;
+        self
+    }
+
+    /// Sets whether the search is case sensitive initially.
+    /// Default is false
+    #[inline]
+    pub fn search_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.search_case_sensitive = case_sensitive;
+        self.search_changed = true;
+        self
+    }
+
+    /// Sets whether the search term is a regex initially.
+    /// Only has an effect if regex is enabled.
+    /// Default is false
+    #[inline]
+    pub fn search_use_regex(mut self, use_regex: bool) -> Self {
+        self.search_mode = if use_regex {
+            SearchMode::Regex
+        } else {
+            SearchMode::Plain
+        };
+        self.search_changed = true;
+        self
+    }
+
+    /// Sets how the search term is matched initially, it can still be changed next to the search bar.
+    /// [`SearchMode::Regex`] only has an effect if regex is enabled.
+    /// Default is [`SearchMode::Plain`]
+    #[inline]
+    pub fn search_mode(mut self, mode: SearchMode) -> Self {
+        self.search_mode = mode;
+        self.search_changed = true;
+        self
+    }
+
+    /// Sets the id the state of this logger ui is stored under.
+    /// Loggers with different ids have independent filters and search,
+    /// e.g. to show an "errors" and an "all logs" window.
+    /// Default is the same id for all loggers
+    #[inline]
+    pub fn id(mut self, id: egui::Id) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Shows the records of `store` instead of the global buffer.
+    /// Use a different [id](Self::id) for each store, as the state is stored by id.
+    /// Default is the global buffer
+    #[inline]
+    pub fn with_store(mut self, store: &LogStore) -> Self {
+        self.store = Some(store.clone());
+        self
+    }
+
+    /// Returns the stored state for the id of this LoggerUi, or stores this one if there is none yet.
+    pub(crate) fn log_ui(self) -> Arc<Mutex<LoggerUi>> {
+        let mut logger_uis = lock_logger_uis();
+        logger_uis
+            .entry(self.id)
+            .or_insert_with(|| Arc::new(self.into()))
+            .clone()
+    }
+
+    /// Returns true if errors were logged that the logger ui with this id hasn't shown before,
+    /// either during its last [`show()`](Self::show) or since then.
+    /// Use this to open or focus the log window when an error arrives,
+    /// see [`has_new_errors()`](crate::has_new_errors) for a signal that isn't tied to a logger ui.
+    ///
+    /// ```rust
+    /// # let ctx = egui::Context::default();
+    /// # let mut open = false;
+    /// # let _ = ctx.run(Default::default(), |ctx| {
+    /// open |= egui_logger::logger_ui().had_new_error_this_frame();
+    /// egui::Window::new("Log").open(&mut open).show(ctx, |ui| {
+    ///     egui_logger::logger_ui().show(ui);
+    /// });
+    /// # });
+    /// ```
+    pub fn had_new_error_this_frame(&self) -> bool {
+        let error_count = lock_store(self.store.as_ref()).error_count;
+        let logger_uis = lock_logger_uis();
+        let Some(logger_ui) = logger_uis.get(&self.id) else {
+            return error_count > 0;
+        };
+        let Ok(logger_ui) = logger_ui.lock() else {
+            return false;
+        };
+        logger_ui.new_errors_shown || error_count > logger_ui.last_error_count
+    }
+
+    /// Forgets the ui state of the logger ui with this id, e.g. after [`try_show()`](Self::try_show)
+    /// returned [`LoggerError::UiPoisoned`].
+    /// The next [`show()`](Self::show) creates it again from this configuration, the log itself is kept.
+    pub fn reset(self) {
+        lock_logger_uis().remove(&self.id);
+    }
+
+    /// This draws the Logger UI
+    pub fn show(self, ui: &mut egui::Ui) {
+        if self.try_show(ui).is_err() {
+            ui.colored_label(Color32::RED, "Something went wrong loading the log");
+        }
+    }
+
+    /// Draws the logger docked at the bottom of the window, in a panel that can be resized by dragging its top edge.
+    ///
+    /// Call this before adding the [`CentralPanel`](egui::CentralPanel), like any other side panel.
+    pub fn show_bottom_panel(self, ctx: &egui::Context, default_height: f32) {
+        egui::TopBottomPanel::bottom(self.id.with("bottom_panel"))
+            .resizable(true)
+            .default_height(default_height)
+            .show(ctx, |ui| self.show(ui));
+    }
+
+    /// Same as [`show()`](Self::show), but returns an error instead of drawing one
+    /// if the ui state is poisoned.
+    /// Call [`reset()`](Self::reset) to recover from it.
+    pub fn try_show(self, ui: &mut egui::Ui) -> Result<(), LoggerError> {
+        let logger_ui = self.log_ui();
+        let Ok(ref mut logger_ui) = logger_ui.lock() else {
+            return Err(LoggerError::UiPoisoned);
+        };
+        logger_ui.ui(ui);
+        Ok(())
+    }
+
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui) {
+        let store = self.store.clone();
+        self.run_filter(store.as_ref());
+        let deferred = {
+            let mut logger = lock_store(store.as_ref());
+            self.logs_ui(ui, &mut logger)
+        };
+
+        // the callbacks run after the buffer is unlocked, so they can log
+        if self.new_errors_shown {
+            if let Some(on_new_error) = &mut self.on_new_error {
+                on_new_error();
+            }
+        }
+        if let Some(text) = deferred.copied {
+            match &mut self.on_copy {
+                Some(on_copy) => on_copy(text),
+                None => ui.ctx().copy_text(text),
+            }
+        }
+        if let Some(menu_action) = deferred.menu_action {
+            menu_action();
+        }
+    }
+
+    /// Passes copies of the new records to the [filter predicate](Self::filter),
+    /// so it runs while the buffer is unlocked.
+    fn run_filter(&mut self, store: Option<&LogStore>) {
+        let Some(filter) = self.style.filter.clone() else {
+            return;
+        };
+
+        let entries: Vec<LogEntry> = {
+            let logger = lock_store(store);
+            if self.filter_results.accepted.len() > logger.logs.len() {
+                // forget the records that have been removed from the log
+                let first_seq = logger
+                    .logs
+                    .first()
+                    .map_or(logger.next_seq, |record| record.seq);
+                self.filter_results
+                    .accepted
+                    .retain(|&seq, _| seq >= first_seq);
+            }
+            let new = logger
+                .logs
+                .partition_point(|record| record.seq < self.filter_results.next_seq);
+            self.filter_results.next_seq = logger.next_seq;
+            logger.logs[new..].iter().map(LogEntry::from).collect()
+        };
+
+        for entry in entries {
+            let accepted = filter(&entry);
+            self.filter_results.accepted.insert(entry.seq, accepted);
+        }
+    }
+
+    /// Draws the logger while the buffer is locked.
+    /// Returns what has to wait until the buffer is unlocked,
+    /// e.g. the copied text for the [`on_copy()`](Self::on_copy) callback or the clipboard.
+    fn logs_ui(&mut self, ui: &mut egui::Ui, logger: &mut Logger) -> Deferred {
+        self.new_errors_shown = logger.error_count > self.last_error_count;
+        self.last_error_count = logger.error_count;
+
+        logger.truncate(self.max_log_length);
+
+        if self.style.auto_colors {
+            let visuals = ui.visuals();
+            let custom_colors = self.style.custom_colors;
+            if !custom_colors.warn {
+                self.style.warn_color = visuals.warn_fg_color;
+            }
+            if !custom_colors.error {
+                self.style.error_color = visuals.error_fg_color;
+            }
+            if !custom_colors.highlight {
+                self.style.highlight_color = visuals.strong_text_color();
+            }
+        }
+
+        if self.style.enable_toolbar {
+            self.toolbar_ui(ui, logger);
+        }
+        // the toolbar compiles the search right away, without it a preset search is compiled here
+        if self.search_changed {
+            self.compile_search();
+        }
+
+        // the time of the newest record is usually the widest,
+        // except for "Time Ago" where it's the oldest
+        let padding_record = match self.style.format.time_format {
+            TimeFormat::Ago => logger.logs.first(),
+            _ => logger.logs.last(),
+        };
+        let mut padding = Padding {
+            seq: logger.next_seq.saturating_sub(1).to_string().len(),
+            time: padding_record.map_or(0, |record| {
+                format::format_time(record.time, &self.style.format, logger.start_time).len()
+            }),
+            target: self
+                .style
+                .max_target_width
+                .map_or(logger.max_category_length, |max_chars| {
+                    logger.max_category_length.min(max_chars)
+                }),
+        };
+        if self.style.stable_columns {
+            // only ever grow the columns, so the text doesn't jump around
+            self.stable_padding.seq = self.stable_padding.seq.max(padding.seq);
+            self.stable_padding.time = self.stable_padding.time.max(padding.time);
+            self.stable_padding.target = self.stable_padding.target.max(padding.target);
+            padding = self.stable_padding;
+        }
+
+        if self.style.format.time_format == TimeFormat::Ago {
+            // keep the relative times up to date
+            ui.ctx()
+                .request_repaint_after(match self.style.format.time_precision {
+                    TimePrecision::Seconds => std::time::Duration::from_secs(1),
+                    TimePrecision::Milliseconds => std::time::Duration::from_millis(100),
+                });
+        } else if self.style.fade_old.is_some() || self.style.show_rate_sparkline {
+            // keep fading the records and moving the sparkline
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_secs(1));
+        }
+
+        self.update_filter_cache(logger, padding);
+        if self.filter_cache.next_seq < logger.next_seq {
+            // the filter predicate hasn't seen the newest records yet
+            ui.ctx().request_repaint();
+        }
+        self.clamp_selection();
+        let logs_displayed = self.filter_cache.visible.len();
+
+        if self.style.show_rate_sparkline {
+            self.sparkline_ui(ui, logger);
+        }
+
+        let hidden = self.style.display_limit.map_or(0, |limit| {
+            logs_displayed.saturating_sub(limit.saturating_add(self.display_more))
+        });
+        if let Some(limit) = self.style.display_limit.filter(|_| hidden > 0) {
+            ui.horizontal(|ui| {
+                ui.label(format!("{hidden} older records hidden"));
+                if ui.button("Show more").clicked() {
+                    self.display_more = self.display_more.saturating_add(limit);
+                }
+            });
+        }
+
+        let mut deferred = Deferred::default();
+        let mut row_event = None;
+        if !self.pinned.is_empty() {
+            ui.label(RichText::new("Pinned").strong());
+            for &seq in &self.pinned {
+                let Some(record) = logger.record(seq) else {
+                    continue;
+                };
+                ui.horizontal(|ui| {
+                    if self.filter_cache.visible.binary_search(&seq).is_err() {
+                        ui.weak("(filtered)");
+                    }
+                    if let Some(event) = self.record_ui(ui, logger, record, padding) {
+                        row_event = Some((seq, event));
+                    }
+                });
+            }
+            ui.separator();
+        }
+
+        let displayed = &self.filter_cache.visible[hidden..];
+
+        let scroll_area = if self.style.horizontal_scroll {
+            egui::ScrollArea::both()
+        } else {
+            egui::ScrollArea::vertical()
+        };
+
+        let footer_height = if self.style.enable_footer {
+            self.footer_height
+                .unwrap_or_else(|| ui.spacing().interact_size.y + ui.spacing().item_spacing.y)
+        } else {
+            0.0
+        };
+
+        let scroll_area = scroll_area
+            .id_salt(self.id)
+            .auto_shrink([false, !self.style.fill_height])
+            .max_height(
+                self.style
+                    .max_height
+                    .unwrap_or(ui.available_height() - footer_height),
+            )
+            .stick_to_bottom(self.follow);
+
+        // restore the scroll position if the log wasn't shown in the last frame,
+        // e.g. because its window was closed
+        let scroll_offset_id = self.id.with("scroll_offset");
+        let pass_nr = ui.ctx().cumulative_pass_nr();
+        let reopened = self
+            .last_pass_nr
+            .is_none_or(|last_pass_nr| last_pass_nr + 1 < pass_nr);
+        self.last_pass_nr = Some(pass_nr);
+        let scroll_area = match ui.data(|data| data.get_temp::<egui::Vec2>(scroll_offset_id)) {
+            Some(offset) if reopened => scroll_area.scroll_offset(offset),
+            _ => scroll_area,
+        };
+
+        let visible_records = displayed.iter().filter_map(|&seq| logger.record(seq));
+
+        if let Some(delimiter) = self.style.column_delimiter {
+            self.column_width = visible_records
+                .clone()
+                .filter_map(|record| split_column(&record.message, delimiter))
+                .map(|(column, _)| column.chars().count())
+                .max()
+                .unwrap_or_default();
+        }
+
+        let scroll_offset = if self.style.group_by_category {
+            scroll_area.show(ui, |ui| {
+                self.scroll_area_style(ui);
+
+                let mut groups: BTreeMap<&str, Vec<&Record>> = BTreeMap::new();
+                for record in visible_records {
+                    groups.entry(&record.target).or_default().push(record);
+                }
+
+                for (target, records) in groups {
+                    egui::CollapsingHeader::new(format!("{target} ({})", records.len()))
+                        .id_salt(target)
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            let mut previous_date = None;
+                            for record in records {
+                                self.date_separator_ui(ui, &mut previous_date, record);
+                                if let Some(event) = self.record_ui(ui, logger, record, padding) {
+                                    row_event = Some((record.seq, event));
+                                }
+                            }
+                        });
+                }
+            })
+        } else {
+            // only the rows in view are laid out, the others take the height they had when last
+            // drawn, or an estimate from their number of lines
+            let line_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            let mut row_heights = std::mem::take(&mut self.row_heights);
+            let output = scroll_area.show_viewport(ui, |ui, viewport| {
+                self.scroll_area_style(ui);
+
+                let width = ui.available_width();
+                if row_heights.width != width {
+                    row_heights = RowHeights {
+                        width,
+                        heights: HashMap::new(),
+                    };
+                } else if row_heights.heights.len() > 2 * displayed.len() {
+                    // forget the rows that were dropped or filtered out
+                    let shown: HashSet<u64> = displayed.iter().copied().collect();
+                    row_heights.heights.retain(|seq, _| shown.contains(seq));
+                }
+                let spacing = ui.spacing().item_spacing.y;
+                let row_height = |heights: &mut HashMap<u64, f32>, seq: u64| {
+                    *heights.entry(seq).or_insert_with(|| {
+                        let lines = logger
+                            .record(seq)
+                            .map_or(1, |record| record.message.lines().count().max(1));
+                        lines as f32 * line_height + spacing
+                    })
+                };
+
+                let mut top = 0.0;
+                let mut first = displayed.len();
+                for (i, &seq) in displayed.iter().enumerate() {
+                    let height = row_height(&mut row_heights.heights, seq);
+                    if top + height > viewport.min.y {
+                        first = i;
+                        break;
+                    }
+                    top += height;
+                }
+                ui.add_space(top);
+
+                let mut previous_seq = first.checked_sub(1).map(|i| displayed[i]);
+                let mut previous_date = previous_seq
+                    .and_then(|seq| logger.record(seq))
+                    .map(|record| self.record_date(record));
+                let mut end = first;
+                while end < displayed.len() && top < viewport.max.y {
+                    let seq = displayed[end];
+                    let start = ui.cursor().min.y;
+                    if let Some(record) = logger.record(seq) {
+                        omitted_marker_ui(ui, logger, previous_seq, seq);
+                        self.date_separator_ui(ui, &mut previous_date, record);
+                        if let Some(event) = self.record_ui(ui, logger, record, padding) {
+                            row_event = Some((seq, event));
+                        }
+                    }
+                    let height = ui.cursor().min.y - start;
+                    row_heights.heights.insert(seq, height);
+                    top += height;
+                    previous_seq = Some(seq);
+                    end += 1;
+                }
+
+                let rest: f32 = displayed[end..]
+                    .iter()
+                    .map(|&seq| row_height(&mut row_heights.heights, seq))
+                    .sum();
+                ui.add_space(rest);
+            });
+            self.row_heights = row_heights;
+            output
+        }
+        .state
+        .offset;
+        ui.data_mut(|data| data.insert_temp(scroll_offset_id, scroll_offset));
+
+        match row_event {
+            Some((seq, RowEvent::Clicked)) => self.select(seq, ui.input(|i| i.modifiers.shift)),
+            Some((seq, RowEvent::ToggleExpanded)) if self.expanded.contains(&seq) => {
+                self.expanded.remove(&seq);
+            }
+            Some((seq, RowEvent::ToggleExpanded)) => {
+                self.expanded.insert(seq);
+            }
+            Some((seq, RowEvent::TogglePinned)) if self.pinned.contains(&seq) => {
+                self.pinned.remove(&seq);
+            }
+            Some((seq, RowEvent::TogglePinned)) => {
+                self.pinned.insert(seq);
+            }
+            Some((_, RowEvent::Copy(text))) => deferred.copied = Some(text),
+            Some((_, RowEvent::MenuAction(menu_action))) => {
+                deferred.menu_action = Some(menu_action);
+            }
+            None => {}
+        }
+        if !self.expanded.is_empty() {
+            self.expanded.retain(|&seq| logger.record(seq).is_some());
+        }
+        if !self.pinned.is_empty() {
+            self.pinned.retain(|&seq| logger.record(seq).is_some());
+        }
+
+        if self.selection.is_some()
+            && ui.memory(|memory| memory.focused().is_none())
+            && ui.input(|i| i.events.contains(&egui::Event::Copy))
+        {
+            deferred.copied = Some(self.selection_text(logger, padding));
+        }
+
+        if self.style.enable_footer {
+            if let Some(text) = self.footer_ui(ui, logger, logs_displayed, padding) {
+                deferred.copied = Some(text);
+            }
+        }
+        deferred
+    }
+
+    /// Draws the rows above the log: the buttons and menus, the search and the max log output.
+    fn toolbar_ui(&mut self, ui: &mut egui::Ui, logger: &mut Logger) {
+        ui.horizontal(|ui| {
+            if self.style.enable_clear_button {
+                let clear_shortcut = self.style.enable_shortcuts
+                    && ui.input_mut(|i| {
+                        i.consume_shortcut(&egui::KeyboardShortcut::new(
+                            egui::Modifiers::COMMAND,
+                            egui::Key::L,
+                        ))
+                    });
+
+                let mut clear = clear_shortcut;
+                if self.style.confirm_clear {
+                    ui.menu_button("Clear", |ui| {
+                        ui.label("Clear the whole log?");
+                        if ui.button("Clear").clicked() {
+                            clear = true;
+                            ui.close_menu();
+                        }
+                    });
+                } else if ui.button("Clear").clicked() {
+                    clear = true;
+                }
+
+                if clear {
+                    logger.clear();
+                }
+            }
+            if self.style.level_chips {
+                for level in all_levels() {
+                    let mut chip = RichText::new(&level.as_str()[..1]).monospace().strong();
+                    if let Some(color) = level_color(&self.style, level) {
+                        chip = chip.color(color);
+                    }
+                    if ui
+                        .selectable_label(self.loglevels[level_index(level)], chip)
+                        .on_hover_text(level.as_str())
+                        .clicked()
+                    {
+                        self.toggle_level(level);
+                    }
+                }
+            } else if self.style.level_dropdown {
+                // the least severe shown level, if the shown levels are all at or above it
+                let shown = self.loglevels.iter().take_while(|&&shown| shown).count();
+                let min_level = self.loglevels[shown..]
+                    .iter()
+                    .all(|&shown| !shown)
+                    .then(|| shown.checked_sub(1).map(|index| LEVELS[index]))
+                    .flatten();
+                egui::ComboBox::from_id_salt(self.id.with("min_level"))
+                    .selected_text(min_level.map_or("Custom", |level| level.as_str()))
+                    .show_ui(ui, |ui| {
+                        for level in all_levels() {
+                            if ui
+                                .selectable_label(min_level == Some(level), level.as_str())
+                                .clicked()
+                            {
+                                self.loglevels = LEVELS.map(|shown| shown <= level);
+                                self.errors_only_restore = None;
+                                self.filter_cache.valid = false;
+                            }
+                        }
+                    });
+            } else {
+                ui.menu_button("Log Levels", |ui| {
+                    for level in all_levels() {
+                        if ui
+                            .selectable_label(self.loglevels[level_index(level)], level.as_str())
+                            .clicked()
+                        {
+                            self.toggle_level(level);
+                        }
+                    }
+
+                    // changing what is captured needs the logger installed by init()
+                    if let Some(installed) = crate::installed_logger() {
+                        ui.separator();
+                        let current = installed.base_level();
+                        ui.menu_button(format!("Capture up to {current}"), |ui| {
+                            for level in log::LevelFilter::iter() {
+                                if ui
+                                    .selectable_label(level == current, level.as_str())
+                                    .clicked()
+                                {
+                                    installed.set_max_level(level);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                });
+            }
+
+            if ui
+                .selectable_label(self.errors_only_restore.is_some(), "Errors only")
+                .clicked()
+            {
+                if let Some(loglevels) = self.errors_only_restore.take() {
+                    self.loglevels = loglevels;
+                } else {
+                    self.errors_only_restore = Some(self.loglevels);
+                    self.loglevels = [true, false, false, false, false];
+                }
+                self.filter_cache.valid = false;
+            }
+
+            if ui
+                .selectable_label(self.follow, "Follow")
+                .on_hover_text("Scroll to new log records")
+                .clicked()
+            {
+                self.follow = !self.follow;
+            }
+
+            ui.menu_button("Categories", |ui| {
+                if ui.button("Select All").clicked() {
+                    logger.update_categories(|_, _| true);
+                }
+
+                if ui.button("Unselect All").clicked() {
+                    logger.update_categories(|_, _| false);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.category_pattern)
+                            .hint_text("egui_glow::*")
+                            .desired_width(120.0),
+                    )
+                    .on_hover_text("Wildcard pattern, * matches anything");
+                    if ui.button("Enable").clicked() {
+                        logger.enable_category(&self.category_pattern, true);
+                    }
+                    if ui.button("Disable").clicked() {
+                        logger.enable_category(&self.category_pattern, false);
+                    }
+                });
+
+                ui.separator();
+
+                let mut only_category = None;
+                let mut toggled_category = None;
+                for (category, &enabled) in &logger.categories {
+                    let response = ui
+                        .selectable_label(enabled, category)
+                        .on_hover_text("Right click or Ctrl + click to show only this category");
+                    if response.secondary_clicked()
+                        || (response.clicked() && ui.input(|i| i.modifiers.command))
+                    {
+                        only_category = Some(category.clone());
+                    } else if response.clicked() {
+                        toggled_category = Some(category.clone());
+                    }
+                }
+
+                if let Some(only_category) = only_category {
+                    logger.update_categories(|category, _| category == only_category);
+                } else if let Some(toggled_category) = toggled_category {
+                    logger.update_categories(|category, enabled| {
+                        enabled != (category == toggled_category)
+                    });
+                }
+            });
+
+            ui.menu_button("Time", |ui| {
+                // the search matches the formatted time as well
+                let mut changed = false;
+                changed |= ui
+                    .radio_value(&mut self.style.format.time_format, TimeFormat::Utc, "UTC")
+                    .changed();
+                changed |= ui
+                    .radio_value(
+                        &mut self.style.format.time_format,
+                        TimeFormat::LocalTime,
+                        "Local Time",
+                    )
+                    .changed();
+                changed |= ui
+                    .radio_value(
+                        &mut self.style.format.time_format,
+                        TimeFormat::LocalDateTime,
+                        "Local Date and Time",
+                    )
+                    .changed();
+                changed |= ui
+                    .radio_value(
+                        &mut self.style.format.time_format,
+                        TimeFormat::SinceStart,
+                        "Since Start",
+                    )
+                    .changed();
+                changed |= ui
+                    .radio_value(
+                        &mut self.style.format.time_format,
+                        TimeFormat::Ago,
+                        "Time Ago",
+                    )
+                    .changed();
+
+                ui.separator();
+
+                changed |= ui
+                    .radio_value(
+                        &mut self.style.format.time_precision,
+                        TimePrecision::Seconds,
+                        "Seconds",
+                    )
+                    .changed();
+                changed |= ui
+                    .radio_value(
+                        &mut self.style.format.time_precision,
+                        TimePrecision::Milliseconds,
+                        "Milliseconds",
+                    )
+                    .changed();
+
+                if changed {
+                    self.filter_cache.valid = false;
+                }
+            });
+
+            if self.style.enable_colors_button {
+                ui.menu_button("Colors", |ui| {
+                    let custom_colors = &mut self.style.custom_colors;
+                    for (color, custom, label) in [
+                        (
+                            &mut self.style.error_color,
+                            &mut custom_colors.error,
+                            "Error",
+                        ),
+                        (
+                            &mut self.style.warn_color,
+                            &mut custom_colors.warn,
+                            "Warning",
+                        ),
+                        (
+                            &mut self.style.highlight_color,
+                            &mut custom_colors.highlight,
+                            "Highlight",
+                        ),
+                    ] {
+                        ui.horizontal(|ui| {
+                            // a picked color isn't replaced by the theme color anymore
+                            *custom |= egui::color_picker::color_edit_button_srgba(
+                                ui,
+                                color,
+                                egui::color_picker::Alpha::Opaque,
+                            )
+                            .changed();
+                            ui.label(label);
+                        });
+                    }
+                });
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Search: ");
+            let mut search_edit = egui::TextEdit::singleline(&mut self.search_term);
+            if self.search_error.is_some() {
+                search_edit = search_edit.text_color(self.style.error_color);
+            }
+            let mut response = ui.add(search_edit);
+            if let Some(error) = &self.search_error {
+                response = response.on_hover_text(error.to_string());
+            }
+
+            let mut config_changed = false;
+
+            if self.style.enable_shortcuts {
+                if ui.input_mut(|i| {
+                    i.consume_shortcut(&egui::KeyboardShortcut::new(
+                        egui::Modifiers::COMMAND,
+                        egui::Key::F,
+                    ))
+                }) {
+                    response.request_focus();
+                }
+
+                // the text edit gives up focus when escape is pressed
+                if (response.has_focus() || response.lost_focus())
+                    && ui.input(|i| i.key_pressed(egui::Key::Escape))
+                    && !self.search_term.is_empty()
+                {
+                    self.search_term.clear();
+                    config_changed = true;
+                }
+            }
+
+            if ui
+                .selectable_label(self.search_case_sensitive, "Aa")
+                .on_hover_text("Case sensitive")
+                .clicked()
+            {
+                self.search_case_sensitive = !self.search_case_sensitive;
+                config_changed = true;
+            }
+
+            if self.style.enable_regex
+                && ui
+                    .selectable_label(self.search_mode == SearchMode::Regex, ".*")
+                    .on_hover_text("Use regex")
+                    .clicked()
+            {
+                self.search_mode = match self.search_mode {
+                    SearchMode::Regex => SearchMode::Plain,
+                    _ => SearchMode::Regex,
+                };
+                config_changed = true;
+            }
+
+            if ui
+                .selectable_label(self.search_mode == SearchMode::Fuzzy, "~")
+                .on_hover_text("Fuzzy search")
+                .clicked()
+            {
+                self.search_mode = match self.search_mode {
+                    SearchMode::Fuzzy => SearchMode::Plain,
+                    _ => SearchMode::Fuzzy,
+                };
+                config_changed = true;
+            }
+
+            if response.changed() || config_changed || self.search_changed {
+                self.compile_search();
+            }
+
+            if let Some(error) = &self.search_error {
+                ui.colored_label(self.style.error_color, "invalid regex")
+                    .on_hover_text(error.to_string());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Max Log output");
+            ui.add(egui::widgets::DragValue::new(&mut self.max_log_length).speed(1));
+        });
+
+        ui.separator();
+    }
+
+    /// Compiles the search query after the search changed,
+    /// keeping the last valid query if the new one is invalid.
+    fn compile_search(&mut self) {
+        self.search_changed = false;
+        match SearchQuery::new(
+            &self.search_term,
+            self.search_case_sensitive,
+            match self.search_mode {
+                SearchMode::Regex if !self.style.enable_regex => SearchMode::Plain,
+                mode => mode,
+            },
+        ) {
+            Ok(query) => {
+                if query.narrows(&self.query) {
+                    self.filter_cache.narrow = true;
+                } else {
+                    self.filter_cache.valid = false;
+                }
+                self.query = query;
+                self.search_error = None;
+            }
+            Err(error) => self.search_error = Some(error),
+        }
+    }
+
+    /// Draws the row below the log with the record counts and the Copy menu.
+    fn footer_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        logger: &Logger,
+        logs_displayed: usize,
+        padding: Padding,
+    ) -> Option<String> {
+        let mut copied = None;
+        let footer = ui.horizontal(|ui| {
+            ui.label(format!("Log size: {}", logger.logs.len()));
+            if logger.dropped_count > 0 {
+                ui.label(format!("({} dropped)", logger.dropped_count))
+                    .on_hover_text(
+                        "Records were removed because the log exceeded its capacity, \
+                         the max log length or the retention time, \
+                         or to keep only the first and last records",
+                    );
+            }
+            ui.label(format!("Displayed: {}", logs_displayed));
+            if self.style.show_hidden_counts && !self.filter_cache.hidden.is_empty() {
+                let counts = [
+                    (HiddenBy::Level, "level"),
+                    (HiddenBy::Category, "category"),
+                    (HiddenBy::Search, "search"),
+                    (HiddenBy::Filter, "filter"),
+                ]
+                .into_iter()
+                .filter_map(|(hidden_by, label)| {
+                    let count = self
+                        .filter_cache
+                        .hidden
+                        .iter()
+                        .filter(|&&(_, reason)| reason == hidden_by)
+                        .count();
+                    (count > 0).then(|| format!("{count} by {label}"))
+                })
+                .collect::<Vec<_>>();
+                ui.label(format!("Hidden: {}", counts.join(", ")));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.menu_button("Copy", |ui| {
+                    for (format, label) in [
+                        (CopyFormat::Full, "Full"),
+                        (CopyFormat::MessageOnly, "Message only"),
+                        (CopyFormat::Csv, "CSV"),
+                    ] {
+                        if ui.button(label).clicked() {
+                            copied = Some(self.copy_text(logger, format, padding));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+        });
+
+        self.footer_height = Some(footer.response.rect.height() + ui.spacing().item_spacing.y);
+        copied
+    }
+
+    /// Filters the records that have been logged since the last frame,
+    /// or all records if the filters changed.
+    fn update_filter_cache(&mut self, logger: &Logger, padding: Padding) {
+        if !self.filter_cache.valid
+            || self.filter_cache.categories_generation != logger.categories_generation
+        {
+            self.filter_cache = FilterCache {
+                categories_generation: logger.categories_generation,
+                valid: true,
+                ..Default::default()
+            };
+        }
+
+        if self.filter_cache.narrow {
+            let visible = std::mem::take(&mut self.filter_cache.visible);
+            for seq in visible {
+                let Some(record) = logger.record(seq) else {
+                    continue;
+                };
+                match self.hidden_by(logger, record, padding) {
+                    Some(hidden_by) => self.filter_cache.hidden.push((seq, hidden_by)),
+                    None => self.filter_cache.visible.push(seq),
+                }
+            }
+            self.filter_cache
+                .hidden
+                .sort_unstable_by_key(|&(seq, _)| seq);
+            self.filter_cache.narrow = false;
+        }
+
+        // forget records that have been removed from the log
+        let first_seq = logger
+            .logs
+            .first()
+            .map_or(logger.next_seq, |record| record.seq);
+        let removed = self
+            .filter_cache
+            .visible
+            .partition_point(|&seq| seq < first_seq);
+        self.filter_cache.visible.drain(..removed);
+        let removed = self
+            .filter_cache
+            .hidden
+            .partition_point(|&(seq, _)| seq < first_seq);
+        self.filter_cache.hidden.drain(..removed);
+        if let Some((head_end, _)) = logger.omitted() {
+            let head_len = logger.logs.partition_point(|record| record.seq <= head_end);
+            let tail_start = logger
+                .logs
+                .get(head_len)
+                .map_or(logger.next_seq, |record| record.seq);
+            let omitted = |seq: u64| seq > head_end && seq < tail_start;
+            self.filter_cache.visible.retain(|&seq| !omitted(seq));
+            self.filter_cache.hidden.retain(|&(seq, _)| !omitted(seq));
+        }
+
+        // records logged after the filter predicate ran are filtered in the next frame
+        let filtered_until = if self.style.filter.is_some() {
+            self.filter_results.next_seq.min(logger.next_seq)
+        } else {
+            logger.next_seq
+        };
+        for record in logger
+            .logs
+            .iter()
+            .skip_while(|record| record.seq < self.filter_cache.next_seq)
+            .take_while(|record| record.seq < filtered_until)
+        {
+            match self.hidden_by(logger, record, padding) {
+                Some(hidden_by) => self.filter_cache.hidden.push((record.seq, hidden_by)),
+                None => self.filter_cache.visible.push(record.seq),
+            }
+        }
+        self.filter_cache.next_seq = self.filter_cache.next_seq.max(filtered_until);
+    }
+
+    /// Returns true if the record passes the category, level and search filters.
+    /// Returns the filter that hides the record, or None if it is visible.
+    fn hidden_by(&self, logger: &Logger, record: &Record, padding: Padding) -> Option<HiddenBy> {
+        if self.style.filter.is_some() {
+            if self.filter_results.accepted.get(&record.seq) != Some(&true) {
+                return Some(HiddenBy::Filter);
+            }
+            if self.style.filter_replaces_builtin {
+                return None;
+            }
+        }
+
+        // Filter out categories that are disabled
+        if let Some(&false) = logger.categories.get(&record.target) {
+            return Some(HiddenBy::Category);
+        }
+
+        // Filter out log levels that are disabled
+        if !self.loglevels[level_index(record.level)] {
+            return Some(HiddenBy::Level);
+        }
+
+        let matches = self.query.is_empty()
+            || self
+                .query
+                .matches(record, &record_text(logger, &self.style, record, padding));
+        (!matches).then_some(HiddenBy::Search)
+    }
+
+    /// Applies the style for the content of the log scroll area.
+    fn scroll_area_style(&self, ui: &mut egui::Ui) {
+        if self.style.horizontal_scroll {
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+        }
+    }
+
+    /// Draws the number of records logged per second over the last [`SPARKLINE_SECONDS`],
+    /// each bar colored by the most common level in that second.
+    fn sparkline_ui(&self, ui: &mut egui::Ui, logger: &Logger) {
+        // the buckets are aligned to the start time, so they don't shift between frames
+        let second =
+            |time: chrono::DateTime<chrono::Local>| (time - logger.start_time).num_seconds();
+        let now = second(crate::now());
+        let first = now - SPARKLINE_SECONDS as i64 + 1;
+
+        let mut buckets = [[0usize; LEVEL_COUNT]; SPARKLINE_SECONDS];
+        for record in logger.logs.iter().rev() {
+            let bucket = second(record.time) - first;
+            if bucket < 0 {
+                break;
+            }
+            if let Some(bucket) = buckets.get_mut(bucket as usize) {
+                bucket[level_index(record.level)] += 1;
+            }
+        }
+
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), SPARKLINE_HEIGHT),
+            egui::Sense::hover(),
+        );
+        let max = buckets
+            .iter()
+            .map(|bucket| bucket.iter().sum::<usize>())
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let bar_width = rect.width() / SPARKLINE_SECONDS as f32;
+        let painter = ui.painter_at(rect);
+        for (i, bucket) in buckets.iter().enumerate() {
+            let count = bucket.iter().sum::<usize>();
+            if count == 0 {
+                continue;
+            }
+            // ties go to the more severe level
+            let dominant = LEVELS
+                .into_iter()
+                .rev()
+                .max_by_key(|&level| bucket[level_index(level)])
+                .unwrap_or(log::Level::Info);
+            let height = rect.height() * count as f32 / max as f32;
+            let left = rect.left() + i as f32 * bar_width;
+            painter.rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(left, rect.bottom() - height),
+                    egui::pos2(left + (bar_width - 1.0).max(1.0), rect.bottom()),
+                ),
+                0.0,
+                badge_color(&self.style, dominant),
+            );
+        }
+        response.on_hover_text(format!(
+            "Records per second over the last {SPARKLINE_SECONDS} seconds, up to {max}"
+        ));
+    }
+
+    /// Draws a divider with the date if it changed since the previous record.
+    fn date_separator_ui(
+        &self,
+        ui: &mut egui::Ui,
+        previous_date: &mut Option<chrono::NaiveDate>,
+        record: &Record,
+    ) {
+        if !self.style.show_date_separators {
+            return;
+        }
+
+        let date = self.record_date(record);
+        if previous_date.is_some_and(|previous_date| previous_date != date) {
+            ui.label(
+                RichText::new(format!("──── {date} ────"))
+                    .monospace()
+                    .weak(),
+            );
+        }
+        *previous_date = Some(date);
+    }
+
+    /// Returns the date of the record in the time zone of the timestamps.
+    fn record_date(&self, record: &Record) -> chrono::NaiveDate {
+        match self.style.format.time_format {
+            TimeFormat::Utc => record.time.to_utc().date_naive(),
+            _ => record.time.date_naive(),
+        }
+    }
+
+    fn toggle_level(&mut self, level: log::Level) {
+        self.loglevels[level_index(level)] = !self.loglevels[level_index(level)];
+        self.errors_only_restore = None;
+        self.filter_cache.valid = false;
+    }
+
+    /// Updates the selection after a record was clicked.
+    /// Shift-click extends the selection from the anchor, a click on the only selected record clears it.
+    fn select(&mut self, seq: u64, extend: bool) {
+        self.selection = match self.selection {
+            Some((anchor, _)) if extend => Some((anchor, seq)),
+            Some((anchor, cursor)) if anchor == seq && cursor == seq => None,
+            _ => Some((seq, seq)),
+        };
+    }
+
+    fn is_selected(&self, seq: u64) -> bool {
+        self.selection.is_some_and(|(anchor, cursor)| {
+            (anchor.min(cursor)..=anchor.max(cursor)).contains(&seq)
+        })
+    }
+
+    /// Shrinks the selection to the visible records after the filters changed,
+    /// clearing it if none of the selected records are visible anymore.
+    fn clamp_selection(&mut self) {
+        let Some((anchor, cursor)) = self.selection else {
+            return;
+        };
+        let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+        let visible = &self.filter_cache.visible;
+        let first = visible.partition_point(|&seq| seq < start);
+        let last = visible.partition_point(|&seq| seq <= end);
+
+        self.selection = if first < last {
+            let (first, last) = (visible[first], visible[last - 1]);
+            if anchor <= cursor {
+                Some((first, last))
+            } else {
+                Some((last, first))
+            }
+        } else {
+            None
+        };
+    }
+
+    /// Formats the selected records for copying.
+    fn selection_text(&self, logger: &Logger, padding: Padding) -> String {
+        self.filter_cache
+            .visible
+            .iter()
+            .filter(|&&seq| self.is_selected(seq))
+            .filter_map(|&seq| logger.record(seq))
+            .map(|record| record_text(logger, &self.style, record, padding))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draws a single record.
+    /// Returns how the record was interacted with, if at all.
+    fn record_ui(
+        &self,
+        ui: &mut egui::Ui,
+        logger: &Logger,
+        record: &Record,
+        padding: Padding,
+    ) -> Option<RowEvent> {
+        let expanded = self.expanded.contains(&record.seq);
+        let truncated = !expanded
+            && self
+                .style
+                .truncate_message
+                .is_some_and(|max_chars| record.message.chars().count() > max_chars);
+        let mut layout_job = format_record(
+            logger,
+            &self.style,
+            record,
+            padding,
+            self.column_width,
+            expanded,
+        );
+        let links = if self.style.linkify {
+            // the message is the last section, unless the time follows it
+            let message_index = match self.style.format.time_position {
+                TimePosition::Leading => layout_job.sections.len() - 1,
+                TimePosition::Trailing => layout_job.sections.len() - 2,
+            };
+            link_urls(&mut layout_job, message_index, ui.visuals().hyperlink_color)
+        } else {
+            Vec::new()
+        };
+
+        // reserve the background shape so it's painted below the text
+        let background = ui.painter().add(egui::Shape::Noop);
+
+        // with links the galley is laid out here like the label would, to find the link under the pointer
+        let galley = (!links.is_empty()).then(|| {
+            match ui.wrap_mode() {
+                egui::TextWrapMode::Extend => layout_job.wrap.max_width = f32::INFINITY,
+                egui::TextWrapMode::Wrap => layout_job.wrap.max_width = ui.available_width(),
+                egui::TextWrapMode::Truncate => {
+                    layout_job.wrap.max_width = ui.available_width();
+                    layout_job.wrap.max_rows = 1;
+                    layout_job.wrap.break_anywhere = true;
+                }
+            }
+            ui.fonts(|fonts| fonts.layout_job(layout_job.clone()))
+        });
+        let label = match &galley {
+            Some(galley) => egui::Label::new(galley.clone()),
+            None => egui::Label::new(layout_job),
+        }
+        .sense(egui::Sense::click());
+        let response = match self.style.fade_old {
+            Some(fade_old) => {
+                let age = (crate::now() - record.time).to_std().unwrap_or_default();
+                let faded = (age.as_secs_f32() / fade_old.as_secs_f32()).min(1.0);
+                ui.scope(|ui| {
+                    ui.multiply_opacity(1.0 - faded * (1.0 - MIN_FADE_OPACITY));
+                    ui.add(label)
+                })
+                .inner
+            }
+            None => ui.add(label),
+        };
+        let target_shortened = self
+            .style
+            .max_target_width
+            .is_some_and(|max_chars| record.target.chars().count() > max_chars);
+        let response = if self.style.time_tooltip || target_shortened {
+            // the tooltip is only built while hovered
+            response.on_hover_ui(|ui| {
+                if self.style.time_tooltip {
+                    ui.label(
+                        record
+                            .time
+                            .with_timezone(&chrono::Utc)
+                            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                    );
+                }
+                if target_shortened {
+                    ui.label(&record.target);
+                }
+            })
+        } else {
+            response
+        };
+
+        if self.is_selected(record.seq) {
+            let rect =
+                egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), response.rect.y_range());
+            ui.painter().set(
+                background,
+                egui::Shape::rect_filled(rect, 0.0, ui.visuals().selection.bg_fill),
+            );
+        } else if self.style.row_background {
+            if let Some(color) = level_color(&self.style, record.level) {
+                let rect =
+                    egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), response.rect.y_range());
+                ui.painter().set(
+                    background,
+                    egui::Shape::rect_filled(rect, 0.0, color.gamma_multiply(0.15)),
+                );
+            }
+        }
+
+        let link = galley
+            .as_ref()
+            .zip(response.hover_pos())
+            .and_then(|(galley, pos)| {
+                let index = galley
+                    .cursor_from_pos(pos - response.rect.left_top())
+                    .ccursor
+                    .index;
+                links
+                    .iter()
+                    .find(|(chars, _)| chars.contains(&index))
+                    .map(|(_, url)| url)
+            });
+        let link_clicked = match link {
+            Some(url) => {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                if response.clicked() {
+                    ui.ctx().open_url(egui::OpenUrl {
+                        url: url.clone(),
+                        new_tab: ui.input(|i| i.modifiers.any()),
+                    });
+                }
+                response.clicked()
+            }
+            None => false,
+        };
+
+        let mut event = if link_clicked {
+            None
+        } else if response.double_clicked() && (truncated || expanded) {
+            Some(RowEvent::ToggleExpanded)
+        } else if response.clicked() {
+            Some(RowEvent::Clicked)
+        } else {
+            None
+        };
+
+        if self.style.enable_ctx_menu {
+            response.clone().context_menu(|ui| {
+                if self.style.show_target || target_shortened {
+                    ui.label(&record.target);
+                }
+                response.clone().highlight();
+                let string_format = format!("[{}]: {}", record.level, record.message);
+
+                // the vertical layout is because otherwise text spacing gets weird
+                ui.vertical(|ui| {
+                    ui.monospace(string_format);
+                });
+
+                if ui.button("Copy").clicked() {
+                    event = Some(RowEvent::Copy(record_text(
+                        logger,
+                        &self.style,
+                        record,
+                        padding,
+                    )));
+                }
+
+                if self.style.format.escape_control_chars
+                    && matches!(format::escape_control_chars(&record.message), Cow::Owned(_))
+                    && ui.button("Copy raw message").clicked()
+                {
+                    event = Some(RowEvent::Copy(record.message.to_string()));
+                }
+
+                if ui.button("Copy with metadata").clicked() {
+                    let text = match &record.location {
+                        Some(location) => format!(
+                            "[{}] {} ({location}): {}",
+                            record.level, record.target, record.message
+                        ),
+                        None => format!("[{}] {}: {}", record.level, record.target, record.message),
+                    };
+                    event = Some(RowEvent::Copy(text));
+                }
+
+                if ui.button("Copy as logfmt").clicked() {
+                    event = Some(RowEvent::Copy(crate::export::logfmt_line(record)));
+                }
+
+                if truncated && ui.button("Expand").clicked() {
+                    event = Some(RowEvent::ToggleExpanded);
+                    ui.close_menu();
+                }
+                if expanded && ui.button("Collapse").clicked() {
+                    event = Some(RowEvent::ToggleExpanded);
+                    ui.close_menu();
+                }
+
+                let pin_label = if self.pinned.contains(&record.seq) {
+                    "Unpin"
+                } else {
+                    "Pin"
+                };
+                if ui.button(pin_label).clicked() {
+                    event = Some(RowEvent::TogglePinned);
+                    ui.close_menu();
+                }
+
+                if let Some(context_menu_extra) = &self.style.context_menu_extra {
+                    ui.separator();
+                    if let Some(menu_action) = context_menu_extra(ui, &LogEntry::from(record)) {
+                        event = Some(RowEvent::MenuAction(menu_action));
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+
+        event
+    }
+
+    /// Formats all records for the Copy button.
+    fn copy_text(&self, logger: &Logger, format: CopyFormat, padding: Padding) -> String {
+        let mut out_string = String::new();
+        if format == CopyFormat::Csv {
+            out_string.push_str("time,level,target,message\n");
+        }
+
+        logger
+            .logs
+            .iter()
+            .take(self.max_log_length)
+            .for_each(|record| match format {
+                CopyFormat::Full => {
+                    let text = record_text(logger, &self.style, record, padding);
+                    match level_color(&self.style, record.level) {
+                        Some(color) if self.style.copy_with_ansi => {
+                            out_string.push_str(&ansi_colored(&text, color));
+                        }
+                        _ => out_string.push_str(&text),
+                    }
+                    out_string.push_str(" \n");
+                }
+                CopyFormat::MessageOnly => {
+                    if self.style.format.escape_control_chars {
+                        out_string.push_str(&format::escape_control_chars(&record.message));
+                    } else {
+                        out_string.push_str(&record.message);
+                    }
+                    out_string.push('\n');
+                }
+                CopyFormat::Csv => {
+                    let time =
+                        format::format_time(record.time, &self.style.format, logger.start_time);
+                    out_string.push_str(&format!(
+                        "{},{},{},{}\n",
+                        csv_field(&time),
+                        record.level,
+                        csv_field(&record.target),
+                        csv_field(&record.message)
+                    ));
+                }
+            });
+
+        out_string
+    }
+}
+
+/// Draws the Logger UI with `ui.add(...)`, same as [`LoggerUi::show()`].
+///
+/// ```rust
+/// # let ctx = egui::Context::default();
+/// # let _ = ctx.run(Default::default(), |ctx| {
+/// egui::Window::new("Log").show(ctx, |ui| {
+///     ui.add(egui_logger::logger_ui().show_target(false));
+/// });
+/// # });
+/// ```
+impl egui::Widget for LoggerUi {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.vertical(|ui| self.show(ui)).response
+    }
+}
+
+/// Quotes a CSV field if necessary.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Returns a default LoggerUi.
+/// You have to call [`LoggerUi::show()`] to display the logger
+pub fn logger_ui() -> LoggerUi {
+    LoggerUi::default()
+}
+
+/// Returns the color a record of this level is highlighted with, if any.
+fn level_color(style: &LoggerStyle, level: log::Level) -> Option<Color32> {
+    match level {
+        log::Level::Warn => Some(style.warn_color),
+        log::Level::Error => Some(style.error_color),
+        _ => None,
+    }
+}
+
+/// Returns the color of the level badge, which unlike [`level_color()`] has a color for every level.
+fn badge_color(style: &LoggerStyle, level: log::Level) -> Color32 {
+    match level {
+        log::Level::Error => style.error_color,
+        log::Level::Warn => style.warn_color,
+        log::Level::Info => style.highlight_color,
+        log::Level::Debug => Color32::GRAY,
+        log::Level::Trace => Color32::DARK_GRAY,
+    }
+}
+
+/// Wraps the text in a 24-bit ANSI foreground color escape sequence.
+fn ansi_colored(text: &str, color: Color32) -> String {
+    format!(
+        "\x1b[38;2;{};{};{}m{text}\x1b[0m",
+        color.r(),
+        color.g(),
+        color.b()
+    )
+}
+
+/// Draws the "… N logs omitted …" marker before the first shown record after the omitted ones,
+/// see [`Retention::HeadTail`](crate::Retention::HeadTail).
+fn omitted_marker_ui(ui: &mut egui::Ui, logger: &Logger, previous_seq: Option<u64>, seq: u64) {
+    let Some((head_end, omitted)) = logger.omitted() else {
+        return;
+    };
+    if seq > head_end && previous_seq.is_none_or(|previous_seq| previous_seq <= head_end) {
+        ui.label(
+            RichText::new(format!("… {omitted} logs omitted …"))
+                .monospace()
+                .weak(),
+        );
+    }
+}
+
+/// Shortens the target to its last `max_chars` characters, including the `…`.
+fn short_target(target: &str, max_chars: Option<usize>) -> Cow<'_, str> {
+    let Some(max_chars) = max_chars else {
+        return Cow::Borrowed(target);
+    };
+    let len = target.chars().count();
+    if len <= max_chars {
+        return Cow::Borrowed(target);
+    }
+    let skip = len - max_chars.saturating_sub(1);
+    let end = target
+        .char_indices()
+        .nth(skip)
+        .map_or(target.len(), |(i, _)| i);
+    Cow::Owned(format!("…{}", &target[end..]))
+}
+
+/// Returns the timestamp and the level/target column of a record as it is drawn,
+/// unlike the copied and searched text of [`record_text()`].
+fn display_prefix(
     logger: &Logger,
     logger_style: &LoggerStyle,
     record: &Record,
-    time_padding: usize,
-) -> LayoutJob {
-    let level_target = format!(
-        "[{:5}] {: <width$}: ",
-        record.level,
-        record.target,
-        width = logger.max_category_length
+    padding: Padding,
+) -> (String, String) {
+    let options = &logger_style.format;
+    let mut date_str = format::format_seq(options, record.seq, padding);
+    // trailing timestamps are added by format_record()
+    if logger_style.format.time_position == TimePosition::Leading {
+        date_str.push_str(&format::format_time_column(
+            options,
+            record.time,
+            logger.start_time,
+            padding,
+        ));
+    }
+
+    let target = format::format_target(
+        options,
+        &short_target(&record.target, logger_style.max_target_width),
+        padding,
     );
+    // the badge replaces the level text, it is drawn by format_record()
+    let level_target = if logger_style.level_badge {
+        target
+    } else {
+        format!("{} {target}", format::format_level(options, record.level))
+    };
+
+    (date_str, level_target)
+}
+
+/// Returns the record as plain text, with the message only escaped if enabled.
+/// This is used for copying and searching.
+fn record_text(
+    logger: &Logger,
+    logger_style: &LoggerStyle,
+    record: &Record,
+    padding: Padding,
+) -> String {
+    format::format_text(
+        &logger_style.format,
+        record.into(),
+        logger.start_time,
+        padding,
+    )
+}
+
+/// Returns the byte ranges of the `http://` and `https://` URLs in the text.
+fn find_urls(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut urls = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("http").map(|start| offset + start) {
+        let rest = &text[start..];
+        let scheme_len = ["https://", "http://"]
+            .into_iter()
+            .find(|scheme| rest.starts_with(scheme))
+            .map_or(0, str::len);
+        let len = rest
+            .find(|c: char| c.is_whitespace() || c.is_control() || "\"'<>`".contains(c))
+            .unwrap_or(rest.len());
+        // trailing punctuation usually belongs to the sentence
+        let len = rest[..len]
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']'])
+            .len();
+        if scheme_len > 0 && len > scheme_len {
+            urls.push(start..start + len);
+            offset = start + len;
+        } else {
+            offset = start + "http".len();
+        }
+    }
+    urls
+}
+
+/// Colors the URLs in the message section of a record and returns their char ranges with the URL.
+fn link_urls(
+    layout_job: &mut LayoutJob,
+    message_index: usize,
+    color: Color32,
+) -> Vec<(std::ops::Range<usize>, String)> {
+    let Some(message) = layout_job.sections.get(message_index).cloned() else {
+        return Vec::new();
+    };
+    let urls = find_urls(&layout_job.text[message.byte_range.clone()]);
+    if urls.is_empty() {
+        return Vec::new();
+    }
+
+    let mut links = Vec::with_capacity(urls.len());
+    let mut sections = Vec::with_capacity(urls.len() * 2 + 1);
+    let mut start = message.byte_range.start;
+    for url in urls {
+        let url = message.byte_range.start + url.start..message.byte_range.start + url.end;
+        sections.push(egui::text::LayoutSection {
+            byte_range: start..url.start,
+            ..message.clone()
+        });
+        let mut format = message.format.clone();
+        format.color = color;
+        format.underline = egui::Stroke::new(1.0, color);
+        sections.push(egui::text::LayoutSection {
+            leading_space: 0.0,
+            byte_range: url.clone(),
+            format,
+        });
+
+        let chars_before = layout_job.text[..url.start].chars().count();
+        let chars = chars_before..chars_before + layout_job.text[url.clone()].chars().count();
+        links.push((chars, layout_job.text[url.clone()].to_string()));
+        start = url.end;
+    }
+    sections.push(egui::text::LayoutSection {
+        leading_space: 0.0,
+        byte_range: start..message.byte_range.end,
+        ..message
+    });
+    layout_job
+        .sections
+        .splice(message_index..=message_index, sections);
+    links
+}
+
+/// Splits the first line of a message at the first delimiter.
+fn split_column(message: &str, delimiter: char) -> Option<(&str, &str)> {
+    message
+        .split_once(delimiter)
+        .filter(|(column, _)| !column.contains('\n'))
+}
+
+fn format_record(
+    logger: &Logger,
+    logger_style: &LoggerStyle,
+    record: &Record,
+    padding: Padding,
+    column_width: usize,
+    expanded: bool,
+) -> LayoutJob {
+    let (date_str, level_target) = display_prefix(logger, logger_style, record, padding);
     let mut layout_job = LayoutJob::default();
     let style = Style::default();
 
-    let mut date_str = RichText::new(format!(
-        "{: >width$} ",
-        format_time(record.time, logger_style, logger.start_time),
-        width = time_padding
-    ))
-    .monospace();
+    // continuation lines of multi-line messages are indented to the message column
+    let badge_width = if logger_style.level_badge { 2 } else { 0 };
+    let indent = date_str.chars().count() + badge_width + level_target.chars().count();
+
+    let mut date_str = RichText::new(date_str).monospace();
     match record.level {
         log::Level::Warn => date_str = date_str.color(logger_style.warn_color),
         log::Level::Error => date_str = date_str.color(logger_style.error_color),
@@ -414,6 +2554,13 @@ fn format_record(
 
     date_str.append_to(&mut layout_job, &style, FontSelection::Default, Align::LEFT);
 
+    if logger_style.level_badge {
+        RichText::new("● ")
+            .monospace()
+            .color(badge_color(logger_style, record.level))
+            .append_to(&mut layout_job, &style, FontSelection::Default, Align::LEFT);
+    }
+
     let highlight_color = match record.level {
         log::Level::Warn => logger_style.warn_color,
         log::Level::Error => logger_style.error_color,
@@ -425,7 +2572,37 @@ fn format_record(
         .color(highlight_color)
         .append_to(&mut layout_job, &style, FontSelection::Default, Align::LEFT);
 
-    let mut message = RichText::new(&record.message).monospace();
+    let mut message = match logger_style.truncate_message {
+        Some(max_chars) if !expanded => match record.message.char_indices().nth(max_chars) {
+            Some((end, _)) => format!("{}…", &record.message[..end]),
+            None => record.message.to_string(),
+        },
+        _ => record.message.to_string(),
+    };
+
+    if let Some(delimiter) = logger_style.column_delimiter {
+        if let Some((column, rest)) = split_column(&message, delimiter) {
+            // whitespace delimiters are replaced, so they don't break the alignment again
+            let delimiter = if delimiter.is_whitespace() {
+                ' '
+            } else {
+                delimiter
+            };
+            message = format!("{column: <column_width$}{delimiter}{rest}");
+        }
+    }
+
+    if logger_style.format.escape_control_chars {
+        if let Cow::Owned(escaped) = format::escape_control_chars(&message) {
+            message = escaped;
+        }
+    }
+
+    if message.contains('\n') {
+        message = message.replace('\n', &format!("\n{: <indent$}", ""));
+    }
+
+    let mut message = RichText::new(message).monospace();
     match record.level {
         log::Level::Warn => message = message.color(logger_style.warn_color),
         log::Level::Error => message = message.color(logger_style.error_color),
@@ -434,5 +2611,73 @@ fn format_record(
 
     message.append_to(&mut layout_job, &style, FontSelection::Default, Align::LEFT);
 
+    if logger_style.format.time_position == TimePosition::Trailing {
+        let mut time = RichText::new(format::format_trailing_time(
+            &logger_style.format,
+            record.time,
+            logger.start_time,
+        ))
+        .monospace();
+        match record.level {
+            log::Level::Warn => time = time.color(logger_style.warn_color),
+            log::Level::Error => time = time.color(logger_style.error_color),
+            _ => {}
+        }
+        time.append_to(&mut layout_job, &style, FontSelection::Default, Align::LEFT);
+    }
+
     layout_job
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(text: &str) -> Vec<&str> {
+        find_urls(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn find_urls_finds_http_and_https() {
+        assert_eq!(
+            urls("fetching http://example.com/a and https://example.com/b?c=d#e"),
+            ["http://example.com/a", "https://example.com/b?c=d#e"]
+        );
+        assert_eq!(urls("https://example.com"), ["https://example.com"]);
+    }
+
+    #[test]
+    fn find_urls_drops_trailing_punctuation() {
+        assert_eq!(
+            urls("see https://example.com/docs. Or (https://example.com/faq), really?"),
+            ["https://example.com/docs", "https://example.com/faq"]
+        );
+        assert_eq!(
+            urls("\"https://example.com/quoted\""),
+            ["https://example.com/quoted"]
+        );
+    }
+
+    #[test]
+    fn find_urls_ignores_a_bare_scheme() {
+        assert!(urls("http").is_empty());
+        assert!(urls("the http client failed: https://").is_empty());
+        assert!(urls("httpx://example.com").is_empty());
+        assert_eq!(urls("http https://example.com"), ["https://example.com"]);
+    }
+
+    #[test]
+    fn find_urls_handles_non_ascii_text() {
+        assert_eq!(
+            urls("größe https://example.com/straße überschritten"),
+            ["https://example.com/straße"]
+        );
+        assert_eq!(
+            urls("ünïcode:https://例え.jp/パス 次"),
+            ["https://例え.jp/パス"]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+#![cfg(all(feature = "ui", feature = "testing"))]
+
+use std::time::Duration;
+
+use egui_logger::{LogStore, LoggerUi, TimeFormat, TimePrecision};
+
+fn logger_ui(store: &LogStore) -> LoggerUi {
+    LoggerUi::default()
+        .id(egui::Id::new("deterministic_render"))
+        .with_store(store)
+        .time_format(TimeFormat::Ago)
+        .time_precision(TimePrecision::Milliseconds)
+        .fade_old(Some(Duration::from_secs(1)))
+        .show_rate_sparkline(true)
+}
+
+/// Renders the logger ui in a fresh context and returns the shapes of the last frame.
+fn render(store: &LogStore) -> Vec<egui::epaint::ClippedShape> {
+    logger_ui(store).reset();
+    let ctx = egui::Context::default();
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(800.0, 600.0),
+        )),
+        ..Default::default()
+    };
+    let mut shapes = Vec::new();
+    // the first frames are used to measure the layout
+    for _ in 0..3 {
+        shapes = ctx
+            .run(input.clone(), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| logger_ui(store).show(ui));
+            })
+            .shapes;
+    }
+    shapes
+}
+
+#[test]
+fn frozen_clock_renders_the_same_every_time() {
+    let time = chrono::DateTime::parse_from_rfc3339("2024-05-01T12:30:05Z")
+        .unwrap()
+        .with_timezone(&chrono::Local);
+    egui_logger::testing::freeze_now(time);
+
+    let store = LogStore::new();
+    store.push_record(log::Level::Info, "app", "started");
+    egui_logger::testing::freeze_now(time + chrono::TimeDelta::milliseconds(1500));
+    store.push_record(log::Level::Warn, "app::net", "slow response");
+    egui_logger::testing::freeze_now(time + chrono::TimeDelta::seconds(3));
+
+    let first = render(&store);
+    // the real clock moves on, the rendered times and fading must not
+    std::thread::sleep(Duration::from_millis(50));
+    let second = render(&store);
+    egui_logger::testing::unfreeze_now();
+
+    assert!(!first.is_empty());
+    assert!(first == second, "the ui rendered differently");
+}
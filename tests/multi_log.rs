@@ -0,0 +1,25 @@
+#![cfg(feature = "capture")]
+
+use log::LevelFilter;
+
+#[test]
+fn multi_log_gets_the_highest_level_and_passes_records_through() {
+    let egui_logger = egui_logger::builder()
+        .max_level(LevelFilter::Info)
+        .filter_target("multi_log_test::net", LevelFilter::Trace)
+        .filter_target("multi_log_test::db", LevelFilter::Warn)
+        .build();
+    assert_eq!(egui_logger.max_level(), LevelFilter::Trace);
+
+    let max_level = egui_logger.max_level().to_level().unwrap();
+    multi_log::MultiLogger::init(vec![Box::new(egui_logger)], max_level).unwrap();
+
+    log::trace!(target: "multi_log_test::net", "connected");
+    log::trace!(target: "multi_log_test::ui", "redrawn");
+
+    let records = egui_logger::query(egui_logger::Query::new().target("multi_log_test"));
+    assert!(records
+        .iter()
+        .any(|record| record.level == log::Level::Trace && record.message == "connected"));
+    assert!(!records.iter().any(|record| record.message == "redrawn"));
+}